@@ -174,6 +174,12 @@ fn write_mime_types(
     // Variants for every MIME.
     let variants = mime_types.keys().map(|mime| mime_ident!(mime));
 
+    // Names for every variant.
+    let names = mime_types.keys().map(|mime| {
+        let ident = mime_ident!(mime);
+        quote! { #ident => #mime }
+    });
+
     // Iterator to get bytes from the extension.
 
     let bytes_iter = match std::env::var("CARGO_CFG_TARGET_FAMILY").as_deref() {
@@ -211,6 +217,14 @@ fn write_mime_types(
                 #bytes_iter
                 #parser
             }
+
+            pub fn name(self) -> &'static str {
+                use MimeType::*;
+
+                match self {
+                    #(#names,)*
+                }
+            }
         }
     };
 
@@ -5,16 +5,22 @@ mod loader;
 mod mimetypes;
 mod serde_impls;
 
+#[cfg(unix)]
+mod permissions;
+
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 
 pub use loader::{load, LoaderError};
 pub use mimetypes::MimeType;
 
+#[cfg(unix)]
+pub use permissions::{lookup_entry, Group, Mode, User};
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[serde(deny_unknown_fields)]
@@ -25,6 +31,9 @@ pub struct Root {
     #[serde(default)]
     pub grid: Grid,
 
+    #[serde(default)]
+    pub time: Time,
+
     #[serde(default)]
     pub columns: Vec<Column>,
 
@@ -43,6 +52,12 @@ pub struct Colors {
     #[serde(default)]
     pub use_lscolors: LsColors,
 
+    #[serde(default)]
+    pub hyperlinks: bool,
+
+    #[serde(default)]
+    pub icons: bool,
+
     pub column_label: Option<Color>,
 
     pub disk_usage: Option<Color>,
@@ -55,6 +70,12 @@ pub struct Colors {
 
     pub diff_deleted: Option<Color>,
 
+    pub markers: Option<Color>,
+
+    pub duplicates: Option<Color>,
+
+    pub heatmap: Option<Heatmap>,
+
     #[serde(default)]
     pub styles: Vec<Style>,
 
@@ -86,6 +107,7 @@ pub struct Style {
     pub matchers: Vec<Matcher>,
     pub color: Option<Color>,
     pub indicator: Option<Indicator>,
+    pub icon: Option<Indicator>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -105,15 +127,150 @@ impl Indicator {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Heatmap {
+    pub by: HeatmapKey,
+
+    pub colors: Gradient,
+
+    pub max_age: Option<Age>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapKey {
+    #[serde(alias = "mtime")]
+    Age,
+
+    Size,
+}
+
+/// List of RGB colors.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct Gradient {
+    pub original: Vec<String>,
+    pub colors: Vec<(u8, u8, u8)>,
+}
+
 #[derive(Serialize, Deserialize, Default)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[serde(deny_unknown_fields)]
 pub struct Grid {
     pub max_rows: Option<NonZeroUsize>,
 
-    pub max_name_width: Option<NonZeroUsize>,
+    pub max_name_width: Option<NameWidth>,
+
+    pub min_name_width: Option<NonZeroUsize>,
+
+    pub truncation: Option<Truncation>,
 
     pub column_padding: Option<usize>,
+
+    #[serde(default)]
+    pub flow: bool,
+
+    pub overflow: Option<Overflow>,
+
+    pub ambiguous_width: Option<AmbiguousWidth>,
+
+    pub quoting_style: Option<QuotingStyle>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Overflow {
+    Wrap,
+    Drop,
+}
+
+/// Maximum width for the file names. With `Auto`, it is computed to fit all
+/// columns in the terminal.
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum NameWidth {
+    Auto,
+    Fixed(NonZeroUsize),
+}
+
+/// How to write file names with special characters.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(rename_all = "kebab-case")]
+pub enum QuotingStyle {
+    Literal,
+
+    Escape,
+
+    Shell,
+
+    #[serde(alias = "shell_always")]
+    ShellAlways,
+
+    C,
+}
+
+/// Width of the East Asian ambiguous characters.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(rename_all = "snake_case")]
+pub enum AmbiguousWidth {
+    Narrow,
+    Wide,
+}
+
+/// Position of the ellipsis when a file name is truncated.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(rename_all = "kebab-case")]
+pub enum Truncation {
+    End,
+
+    Middle,
+
+    #[serde(alias = "keep_extension")]
+    KeepExtension,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Time {
+    pub format: Option<TimeFormat>,
+
+    pub timezone: Option<TimeZone>,
+
+    #[serde(default)]
+    pub styles: Vec<TimeStyle>,
+}
+
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum TimeFormat {
+    Relative,
+    Iso,
+    Pattern(String),
+}
+
+#[derive(Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum TimeZone {
+    Local,
+    Utc,
+    Offset(i32),
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct TimeStyle {
+    pub min_age: Option<Age>,
+
+    pub max_age: Option<Age>,
+
+    pub color: Color,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -125,32 +282,135 @@ pub struct Column {
 
     pub label: Option<String>,
 
-    pub max_name_width: Option<NonZeroUsize>,
+    pub max_name_width: Option<NameWidth>,
+
+    pub truncation: Option<Truncation>,
 
     pub matchers: Vec<Matcher>,
 
     #[serde(default)]
     pub exclude: Vec<Matcher>,
 
+    #[serde(default)]
+    pub pin: Vec<Matcher>,
+
+    #[serde(default = "default_true")]
+    pub exclusive: bool,
+
     #[serde(default = "default_true")]
     pub git_changes_first: bool,
 
     pub color: Option<Color>,
 
     pub sort: Option<SortSpec>,
+
+    #[serde(default)]
+    pub commands: Vec<String>,
+
+    #[serde(default)]
+    pub image_size: bool,
+
+    #[serde(default)]
+    pub fields: Vec<Field>,
+
+    pub split: Option<Split>,
 }
 
-#[derive(Clone, Copy)]
+/// Split the files of a column in multiple columns, one for every value of
+/// the key.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Split {
+    pub by: SplitKey,
+
+    pub max_columns: Option<NonZeroUsize>,
+
+    pub min_files: Option<NonZeroUsize>,
+
+    pub other: Option<String>,
+
+    #[serde(default)]
+    pub buckets: Vec<TimeBucket>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
-pub struct SortSpec(pub SortKey, pub SortOrder);
+#[serde(rename_all = "snake_case")]
+pub enum SplitKey {
+    Extension,
+
+    Mime,
+
+    GitStatus,
+
+    Owner,
+
+    #[serde(alias = "mtime")]
+    ModificationTime,
+
+    #[serde(alias = "deep_mtime")]
+    DeepModificationTime,
+}
+
+/// Column for the files modified in the last `max_age`, when the files are
+/// split by modification time.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct TimeBucket {
+    pub label: String,
+
+    pub max_age: Age,
+}
 
 #[derive(Serialize, Deserialize, Clone, Copy)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[serde(rename_all = "snake_case")]
+pub enum Field {
+    Permissions,
+
+    Links,
+
+    Owner,
+
+    Group,
+
+    Size,
+
+    #[serde(alias = "mtime")]
+    ModificationTime,
+}
+
+/// List of sort keys. Files are compared with the first key, and the next
+/// keys are used only when the previous ones are equal.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct SortSpec(pub Vec<(SortKey, SortOrder, SortModifiers)>);
+
+#[derive(Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+#[serde(rename_all = "snake_case")]
 pub enum SortKey {
+    #[serde(alias = "atime")]
+    AccessTime,
+
+    #[serde(alias = "btime")]
+    BirthTime,
+
+    #[serde(alias = "ctime")]
+    ChangeTime,
+
+    Command(String),
+
     #[serde(alias = "deep_mtime")]
     DeepModificationTime,
 
+    Extension,
+
+    GitChanged,
+
+    GitChanges,
+
     Name,
 
     Size,
@@ -158,6 +418,8 @@ pub enum SortKey {
     #[serde(alias = "mtime")]
     ModificationTime,
 
+    Type,
+
     Version,
 }
 
@@ -169,6 +431,15 @@ pub enum SortOrder {
     Desc,
 }
 
+/// Options to compare file names, for the `name` and `extension` sort keys.
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct SortModifiers {
+    pub ignore_case: bool,
+    pub natural: bool,
+    pub ignore_punctuation: bool,
+}
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[serde(rename_all = "snake_case")]
@@ -176,9 +447,18 @@ pub enum Matcher {
     Any,
     All(Vec<Matcher>),
     Changes(Changes),
+    Command(CommandMatcher),
+    Duplicate,
     Glob(Glob),
+    #[cfg(unix)]
+    Group(Group),
+    Image(ImageMatcher),
     Mime(MimeType),
+    #[cfg(unix)]
+    Mode(Mode),
     Not(Box<Matcher>),
+    #[cfg(unix)]
+    Owner(User),
     Regex(Regex),
     Type(FileType),
 }
@@ -187,6 +467,56 @@ pub enum Matcher {
 pub enum Changes {
     Git,
     Duration(Duration),
+    Range(ChangesRange),
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct ChangesRange {
+    pub newer_than: Option<TimeLimit>,
+
+    pub older_than: Option<TimeLimit>,
+
+    pub between: Option<[TimeLimit; 2]>,
+
+    pub timestamp: Option<Timestamp>,
+}
+
+/// A duration (relative to the current time) or an absolute date.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum TimeLimit {
+    Age(Duration),
+    Date(SystemTime),
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(rename_all = "lowercase")]
+pub enum Timestamp {
+    Mtime,
+    Ctime,
+    Atime,
+    #[serde(alias = "birth")]
+    Btime,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct CommandMatcher {
+    pub name: String,
+    pub equals: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct ImageMatcher {
+    pub min_width: Option<u32>,
+    pub max_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_height: Option<u32>,
 }
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
@@ -298,12 +628,65 @@ pub struct Collector {
     #[serde(default = "default_true")]
     pub git_diff: bool,
 
+    #[serde(default)]
+    pub image_size: bool,
+
     pub timeout: Option<Timeout>,
+
+    #[serde(default)]
+    pub commands: Vec<CommandCollector>,
+
+    pub markers: Option<Markers>,
+
+    pub duplicates: Option<Duplicates>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct CommandCollector {
+    pub name: String,
+
+    pub program: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    pub mode: Option<CommandMode>,
+
+    pub timeout: Option<Timeout>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+#[serde(rename_all = "snake_case")]
+pub enum CommandMode {
+    Directory,
+    Entry,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Markers {
+    pub regex: Option<Regex>,
+
+    pub max_file_size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct Duplicates {
+    pub max_file_size: Option<u64>,
 }
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct Timeout(pub Duration);
 
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct Age(pub Duration);
+
 fn default_true() -> bool {
     true
 }
@@ -313,6 +696,7 @@ impl Default for Root {
         Root {
             colors: Colors::default(),
             grid: Grid::default(),
+            time: Time::default(),
             collector: Collector::default(),
             info: None,
             columns: vec![
@@ -320,21 +704,35 @@ impl Default for Root {
                     include_hidden: false,
                     label: None,
                     max_name_width: None,
+                    truncation: None,
                     matchers: vec![Matcher::Type(FileType::Directory)],
                     exclude: vec![],
+                    pin: vec![],
+                    exclusive: true,
                     git_changes_first: true,
                     color: None,
                     sort: None,
+                    commands: vec![],
+                    image_size: false,
+                    fields: vec![],
+                    split: None,
                 },
                 Column {
                     include_hidden: true,
                     label: None,
                     max_name_width: None,
+                    truncation: None,
                     matchers: vec![Matcher::Any],
                     exclude: vec![],
+                    pin: vec![],
+                    exclusive: true,
                     git_changes_first: true,
                     color: None,
                     sort: None,
+                    commands: vec![],
+                    image_size: false,
+                    fields: vec![],
+                    split: None,
                 },
             ],
         }
@@ -346,7 +744,11 @@ impl Default for Collector {
         Collector {
             disk_usage: true,
             git_diff: true,
+            image_size: false,
             timeout: Some(Timeout(Duration::from_millis(100))),
+            commands: vec![],
+            markers: None,
+            duplicates: None,
         }
     }
 }
@@ -359,6 +761,10 @@ impl Default for LsColors {
 
 impl Default for SortSpec {
     fn default() -> SortSpec {
-        SortSpec(SortKey::Name, SortOrder::Asc)
+        SortSpec(vec![(
+            SortKey::Name,
+            SortOrder::Asc,
+            SortModifiers::default(),
+        )])
     }
 }
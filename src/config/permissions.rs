@@ -0,0 +1,291 @@
+//! Types for the `owner`, `group` and `mode` matchers.
+//!
+//! User and group names are resolved when the configuration is loaded. If a
+//! name does not exist in the system, the matcher will never be successful.
+//!
+//! These matchers are only available in Unix systems.
+
+use std::ffi::CString;
+use std::{mem, ptr};
+
+/// Maximum size of the buffer for the `getpw*_r` and `getgr*_r` functions.
+const MAX_BUFFER_SIZE: usize = 1 << 20;
+
+/// A user, from a name, a numeric ID, or `me` for the current user.
+#[cfg_attr(test, derive(Debug))]
+pub struct User {
+    pub original: String,
+    pub id: Option<u32>,
+}
+
+/// A group, from a name, a numeric ID, or `me` for the group of the current
+/// user.
+#[cfg_attr(test, derive(Debug))]
+pub struct Group {
+    pub original: String,
+    pub id: Option<u32>,
+}
+
+/// Permission bits that must be set or unset in the file mode.
+#[cfg_attr(test, derive(Debug))]
+pub struct Mode {
+    pub original: String,
+    pub set: u32,
+    pub unset: u32,
+
+    /// Bits where at least one of them must be set.
+    pub any: u32,
+}
+
+#[cfg(test)]
+impl PartialEq for User {
+    fn eq(&self, other: &Self) -> bool {
+        self.original.eq(&other.original)
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for Group {
+    fn eq(&self, other: &Self) -> bool {
+        self.original.eq(&other.original)
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for Mode {
+    fn eq(&self, other: &Self) -> bool {
+        self.original.eq(&other.original)
+    }
+}
+
+/// Call a `getpw*_r` or `getgr*_r` function, growing the buffer while it
+/// returns `ERANGE`. If an entry is found, `read` gets its fields before the
+/// buffer is released.
+///
+/// # Safety
+///
+/// `func` must call the C function with the given arguments, and `E` must be
+/// the type of its entries (`passwd` or `group`).
+pub unsafe fn lookup_entry<E, T>(
+    func: impl Fn(*mut E, *mut libc::c_char, usize, *mut *mut E) -> libc::c_int,
+    read: impl FnOnce(&E) -> T,
+) -> Option<T> {
+    let mut buf: Vec<libc::c_char> = vec![0; 4096];
+
+    loop {
+        let mut entry: E = mem::zeroed();
+        let mut result = ptr::null_mut();
+        let ret = func(&mut entry, buf.as_mut_ptr(), buf.len(), &mut result);
+
+        if ret == libc::ERANGE && buf.len() < MAX_BUFFER_SIZE {
+            buf.resize(buf.len() * 2, 0);
+            continue;
+        }
+
+        if result.is_null() {
+            return None;
+        }
+
+        return Some(read(&entry));
+    }
+}
+
+/// Returns the ID of a user or a group, from its name.
+fn lookup_id<E>(
+    name: &str,
+    func: unsafe extern "C" fn(
+        *const libc::c_char,
+        *mut E,
+        *mut libc::c_char,
+        libc::size_t,
+        *mut *mut E,
+    ) -> libc::c_int,
+    read: impl FnOnce(&E) -> u32,
+) -> Option<u32> {
+    let name = CString::new(name).ok()?;
+
+    // SAFETY: `func` is `getpwnam_r` or `getgrnam_r`, and `E` is its entry.
+    unsafe { lookup_entry(|e, b, l, r| func(name.as_ptr(), e, b, l, r), read) }
+}
+
+impl User {
+    pub fn new(original: String) -> User {
+        let id = match original.as_str() {
+            // SAFETY: `geteuid` is always successful.
+            "me" => Some(unsafe { libc::geteuid() }),
+
+            name => name
+                .parse()
+                .ok()
+                .or_else(|| lookup_id(name, libc::getpwnam_r, |e| e.pw_uid)),
+        };
+
+        User { original, id }
+    }
+}
+
+impl Group {
+    pub fn new(original: String) -> Group {
+        let id = match original.as_str() {
+            // SAFETY: `getegid` is always successful.
+            "me" => Some(unsafe { libc::getegid() }),
+
+            name => name
+                .parse()
+                .ok()
+                .or_else(|| lookup_id(name, libc::getgrnam_r, |e| e.gr_gid)),
+        };
+
+        Group { original, id }
+    }
+}
+
+impl Mode {
+    /// Mode for executable files, used by `type: executable`.
+    pub const EXECUTABLE: Mode = Mode {
+        original: String::new(),
+        set: 0,
+        unset: 0,
+        any: 0o111,
+    };
+
+    /// Parse a mode specification.
+    ///
+    /// It can be a comma-separated list of the following items:
+    ///
+    /// * `setuid`, `setgid` or `sticky`.
+    /// * `executable`, if any of the `x` bits is set.
+    /// * A symbolic mode, like in `chmod(1)`: `[ugoa]*[+-][rwx]+`. `+` means
+    ///   that the bits must be set, and `-` that they must be unset.
+    /// * An octal number, with the bits that must be set.
+    pub fn new(original: String) -> Result<Mode, String> {
+        let mut set = 0;
+        let mut unset = 0;
+        let mut any = 0;
+
+        for item in original.split(',').map(str::trim) {
+            match item {
+                "setuid" => set |= 0o4000,
+                "setgid" => set |= 0o2000,
+                "sticky" => set |= 0o1000,
+                "executable" => any |= Mode::EXECUTABLE.any,
+
+                _ if !item.is_empty() && item.bytes().all(|b| (b'0'..=b'7').contains(&b)) => {
+                    set |= u32::from_str_radix(item, 8).map_err(|e| e.to_string())?;
+                }
+
+                _ => {
+                    let op = item
+                        .find(&['+', '-'][..])
+                        .ok_or_else(|| format!("invalid mode: {}", item))?;
+
+                    let (who, perms) = item.split_at(op);
+
+                    let mut who_mask = 0;
+                    for c in who.chars() {
+                        who_mask |= match c {
+                            'u' => 0o700,
+                            'g' => 0o070,
+                            'o' => 0o007,
+                            'a' => 0o777,
+                            _ => return Err(format!("invalid mode: {}", item)),
+                        };
+                    }
+
+                    if who_mask == 0 {
+                        who_mask = 0o777;
+                    }
+
+                    let mut perms_mask = 0;
+                    for c in perms[1..].chars() {
+                        perms_mask |= match c {
+                            'r' => 0o444,
+                            'w' => 0o222,
+                            'x' => 0o111,
+                            _ => return Err(format!("invalid mode: {}", item)),
+                        };
+                    }
+
+                    if perms_mask == 0 {
+                        return Err(format!("invalid mode: {}", item));
+                    }
+
+                    if perms.starts_with('+') {
+                        set |= who_mask & perms_mask;
+                    } else {
+                        unset |= who_mask & perms_mask;
+                    }
+                }
+            }
+        }
+
+        Ok(Mode {
+            original,
+            set,
+            unset,
+            any,
+        })
+    }
+
+    /// Returns `true` if the bits in `mode` match this specification.
+    pub fn is_match(&self, mode: u32) -> bool {
+        mode & self.set == self.set
+            && mode & self.unset == 0
+            && (self.any == 0 || mode & self.any != 0)
+    }
+}
+
+#[test]
+fn parse_mode_specs() {
+    macro_rules! check {
+        ($spec:expr, $set:expr, $unset:expr) => {
+            let mode = Mode::new($spec.into()).unwrap();
+            assert_eq!((mode.set, mode.unset), ($set, $unset));
+        };
+    }
+
+    check!("o+w", 0o002, 0);
+    check!("ug+rx", 0o550, 0);
+    check!("+x", 0o111, 0);
+    check!("a-w", 0, 0o222);
+    check!("u+w, o-r", 0o200, 0o004);
+    check!("setuid,sticky", 0o5000, 0);
+    check!("setgid", 0o2000, 0);
+    check!("0755", 0o755, 0);
+
+    for invalid in ["o=w", "z+w", "o+", "u+q", "w"] {
+        assert!(Mode::new(invalid.into()).is_err(), "{}", invalid);
+    }
+
+    let mode = Mode::new("o+w,u-x".into()).unwrap();
+    assert!(mode.is_match(0o666));
+    assert!(!mode.is_match(0o766));
+    assert!(!mode.is_match(0o664));
+
+    let mode = Mode::new("setuid".into()).unwrap();
+    assert!(mode.is_match(0o4755));
+    assert!(!mode.is_match(0o2755));
+
+    let mode = Mode::new("setgid,sticky".into()).unwrap();
+    assert!(mode.is_match(0o3775));
+    assert!(!mode.is_match(0o2775));
+    assert!(!mode.is_match(0o1777));
+
+    let mode = Mode::new("executable, o-w".into()).unwrap();
+    assert!(mode.is_match(0o744));
+    assert!(mode.is_match(0o610));
+    assert!(!mode.is_match(0o644));
+    assert!(!mode.is_match(0o747));
+
+    assert!(Mode::EXECUTABLE.is_match(0o701));
+    assert!(!Mode::EXECUTABLE.is_match(0o4666));
+}
+
+#[test]
+fn resolve_ids() {
+    assert_eq!(User::new("root".into()).id, Some(0));
+    assert_eq!(User::new("1234".into()).id, Some(1234));
+    assert_eq!(User::new("me".into()).id, Some(unsafe { libc::geteuid() }));
+    assert_eq!(User::new("\0".into()).id, None);
+    assert_eq!(Group::new("me".into()).id, Some(unsafe { libc::getegid() }));
+}
@@ -1,8 +1,12 @@
 //! Implementation for serializers and deserializers for custom types.
 
-use super::{Changes, Color, Glob, Regex, SortOrder, SortSpec, Timeout};
+use super::{
+    Age, Changes, ChangesRange, Color, Glob, Gradient, NameWidth, Regex, SortKey, SortModifiers,
+    SortOrder, SortSpec, TimeFormat, TimeLimit, TimeZone, Timeout,
+};
 use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
 use std::borrow::Cow;
+use std::num::NonZeroUsize;
 
 // `Color`.
 //
@@ -29,6 +33,49 @@ impl<'de> Deserialize<'de> for Color {
     }
 }
 
+// `Gradient`.
+//
+// A list of at least two colors, in Git's color configuration syntax. Only
+// RGB colors (like `#ff8000`) are accepted.
+
+impl Serialize for Gradient {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.original.serialize(ser)
+    }
+}
+
+impl<'de> Deserialize<'de> for Gradient {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let original = Vec::<String>::deserialize(deserializer)?;
+
+        if original.len() < 2 {
+            return Err(de::Error::custom("a gradient needs at least two colors"));
+        }
+
+        let mut colors = Vec::with_capacity(original.len());
+        for color in &original {
+            let style = colorparse::parse(color).map_err(de::Error::custom)?;
+            match style.foreground {
+                Some(ansi_term::Color::RGB(r, g, b)) => colors.push((r, g, b)),
+                _ => {
+                    return Err(de::Error::custom(format!(
+                        "gradient colors must be RGB values: {}",
+                        color
+                    )))
+                }
+            }
+        }
+
+        Ok(Gradient { original, colors })
+    }
+}
+
 // `Glob`.
 //
 // A string or a list of strings, stored as a `globset::Glob`.
@@ -65,7 +112,8 @@ impl<'de> Deserialize<'de> for Glob {
 
 // `Changes`.
 //
-// Can be either a duration (like `3 hours`) or `git`.
+// Can be either a duration (like `3 hours`), `git`, or an object for
+// `ChangesRange`.
 
 impl Serialize for Changes {
     fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
@@ -75,22 +123,159 @@ impl Serialize for Changes {
         match self {
             Changes::Git => ser.serialize_str("git"),
             Changes::Duration(d) => ser.serialize_str(&format!("{} ms", d.as_millis())),
+            Changes::Range(r) => r.serialize(ser),
         }
     }
 }
 
 impl<'de> Deserialize<'de> for Changes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Value<'a> {
+            S(Cow<'a, str>),
+            R(ChangesRange),
+        }
+
+        match Value::deserialize(deserializer)? {
+            Value::S(value) if value == "git" => Ok(Changes::Git),
+
+            Value::S(value) => humantime::parse_duration(&value)
+                .map(Changes::Duration)
+                .map_err(de::Error::custom),
+
+            Value::R(range) => Ok(Changes::Range(range)),
+        }
+    }
+}
+
+// `TimeLimit`.
+//
+// Either a duration (like `3 months`) or a date (like `2021-09-23`, or
+// `2021-09-23 10:20:30`), in UTC.
+
+impl Serialize for TimeLimit {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            TimeLimit::Age(d) => ser.serialize_str(&format!("{} ms", d.as_millis())),
+            TimeLimit::Date(d) => {
+                ser.serialize_str(&humantime::format_rfc3339_seconds(*d).to_string())
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeLimit {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let value = Cow::<str>::deserialize(deserializer)?;
-        if value == "git" {
-            Ok(Changes::Git)
+
+        if let Ok(duration) = humantime::parse_duration(&value) {
+            return Ok(TimeLimit::Age(duration));
+        }
+
+        let date = if value.len() == 10 {
+            humantime::parse_rfc3339_weak(&format!("{} 00:00:00", value))
         } else {
-            humantime::parse_duration(&value)
-                .map(Changes::Duration)
-                .map_err(de::Error::custom)
+            humantime::parse_rfc3339_weak(&value)
+        };
+
+        date.map(TimeLimit::Date)
+            .map_err(|_| de::Error::custom(format!("invalid duration or date: {}", value)))
+    }
+}
+
+// `User`, `Group` and `Mode`.
+//
+// A string, resolved when the configuration is loaded.
+
+#[cfg(unix)]
+macro_rules! permission_serde {
+    ($type:ident, $new:expr) => {
+        impl Serialize for super::$type {
+            fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                ser.serialize_str(&self.original)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for super::$type {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                #[derive(Deserialize)]
+                #[serde(untagged)]
+                enum Value {
+                    S(String),
+                    N(u32),
+                }
+
+                let value = match Value::deserialize(deserializer)? {
+                    Value::S(s) => s,
+                    Value::N(n) => n.to_string(),
+                };
+
+                $new(value).map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+#[cfg(unix)]
+permission_serde!(User, |v| Ok::<_, String>(super::User::new(v)));
+
+#[cfg(unix)]
+permission_serde!(Group, |v| Ok::<_, String>(super::Group::new(v)));
+
+#[cfg(unix)]
+permission_serde!(Mode, super::Mode::new);
+
+// `NameWidth`.
+//
+// Either a number, or `auto`.
+
+impl Serialize for NameWidth {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            NameWidth::Auto => ser.serialize_str("auto"),
+            NameWidth::Fixed(width) => width.serialize(ser),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for NameWidth {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Value {
+            Fixed(NonZeroUsize),
+            Keyword(String),
+        }
+
+        match Value::deserialize(deserializer)? {
+            Value::Fixed(width) => Ok(NameWidth::Fixed(width)),
+            Value::Keyword(k) if k == "auto" => Ok(NameWidth::Auto),
+            Value::Keyword(k) => Err(de::Error::custom(format!(
+                "expected a positive number or 'auto', found '{}'",
+                k
+            ))),
         }
     }
 }
@@ -120,24 +305,56 @@ impl<'de> Deserialize<'de> for Regex {
 
 // `SortSpec`.
 //
-// Sort specification can be either "key" or "key order".
+// Sort specification is a comma-separated list of "key [order] [modifiers]"
+// items. Modifiers are `ignore_case`, `natural`, and `ignore_punctuation`.
+//
+// Values from command collectors are selected with "command:name".
 
 impl Serialize for SortSpec {
     fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        // To reuse the code generated by serde, we serialize a tuple with the
-        // values, and then extract the identifiers from the string.
+        // To reuse the code generated by serde, we serialize the values as
+        // YAML, and then extract the identifiers from the string.
+
+        macro_rules! ident {
+            ($value:expr) => {
+                serde_yaml::to_string($value)
+                    .map_err(ser::Error::custom)?
+                    .trim_start_matches("---")
+                    .trim()
+                    .to_owned()
+            };
+        }
 
-        let yaml = serde_yaml::to_string(&(self.0, self.1)).map_err(ser::Error::custom)?;
+        let mut spec = String::new();
+        for (key, order, modifiers) in &self.0 {
+            if !spec.is_empty() {
+                spec.push_str(", ");
+            }
+
+            match key {
+                SortKey::Command(name) => spec.push_str(&format!("command:{}", name)),
+                key => spec.push_str(&ident!(key)),
+            }
 
-        let string: Vec<_> = yaml
-            .split_whitespace()
-            .filter(|w| w.chars().next().unwrap_or_default().is_alphabetic())
-            .collect();
+            spec.push(' ');
+            spec.push_str(&ident!(order));
+
+            for (enabled, name) in [
+                (modifiers.ignore_case, "ignore_case"),
+                (modifiers.natural, "natural"),
+                (modifiers.ignore_punctuation, "ignore_punctuation"),
+            ] {
+                if enabled {
+                    spec.push(' ');
+                    spec.push_str(name);
+                }
+            }
+        }
 
-        ser.serialize_str(&string.join(" "))
+        ser.serialize_str(&spec)
     }
 }
 
@@ -147,45 +364,155 @@ impl<'de> Deserialize<'de> for SortSpec {
         D: Deserializer<'de>,
     {
         let spec = Cow::<str>::deserialize(deserializer)?;
-        match memchr::memchr(b' ', spec.as_bytes()) {
-            Some(space) => {
-                let (key, order) = spec.split_at(space);
-
-                Ok(SortSpec(
-                    serde_yaml::from_str(key).map_err(de::Error::custom)?,
-                    serde_yaml::from_str(order.trim()).map_err(de::Error::custom)?,
-                ))
+
+        let mut keys = Vec::new();
+        for item in spec.split(',') {
+            let mut words = item.split_whitespace();
+
+            let key = words.next().unwrap_or_default();
+            let key = match key.strip_prefix("command:") {
+                Some(name) => SortKey::Command(name.to_owned()),
+                None => serde_yaml::from_str(key).map_err(de::Error::custom)?,
+            };
+
+            let mut order = SortOrder::Asc;
+            let mut modifiers = SortModifiers::default();
+
+            for word in words {
+                match word {
+                    "ignore_case" => modifiers.ignore_case = true,
+                    "natural" => modifiers.natural = true,
+                    "ignore_punctuation" => modifiers.ignore_punctuation = true,
+                    _ => order = serde_yaml::from_str(word).map_err(de::Error::custom)?,
+                }
             }
 
-            None => Ok(SortSpec(
-                serde_yaml::from_str(&spec).map_err(de::Error::custom)?,
-                SortOrder::Asc,
-            )),
+            keys.push((key, order, modifiers));
         }
+
+        Ok(SortSpec(keys))
     }
 }
 
-// `Timeout`.
+// `Timeout` and `Age`.
 //
 // A duration using the syntax from the humantime crate.
 
-impl Serialize for Timeout {
+macro_rules! duration_serde {
+    ($type:ident) => {
+        impl Serialize for $type {
+            fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                ser.serialize_str(&format!("{} ms", self.0.as_millis()))
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let value = Cow::<str>::deserialize(deserializer)?;
+                humantime::parse_duration(&value)
+                    .map($type)
+                    .map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+duration_serde!(Timeout);
+
+duration_serde!(Age);
+
+// `TimeFormat`.
+//
+// Either `relative`, `iso`, or a pattern with `strftime`-like specifiers.
+
+impl Serialize for TimeFormat {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            TimeFormat::Relative => ser.serialize_str("relative"),
+            TimeFormat::Iso => ser.serialize_str("iso"),
+            TimeFormat::Pattern(p) => ser.serialize_str(p),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Ok(match value.as_str() {
+            "relative" => TimeFormat::Relative,
+            "iso" => TimeFormat::Iso,
+            _ => TimeFormat::Pattern(value),
+        })
+    }
+}
+
+// `TimeZone`.
+//
+// Either `local`, `utc`, or a fixed offset, like `+02:00` or `-0800`.
+
+impl Serialize for TimeZone {
     fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        ser.serialize_str(&format!("{} ms", self.0.as_millis()))
+        match self {
+            TimeZone::Local => ser.serialize_str("local"),
+            TimeZone::Utc => ser.serialize_str("utc"),
+            TimeZone::Offset(offset) => {
+                let sign = if *offset < 0 { '-' } else { '+' };
+                let offset = offset.abs() / 60;
+                ser.serialize_str(&format!("{}{:02}:{:02}", sign, offset / 60, offset % 60))
+            }
+        }
     }
 }
 
-impl<'de> Deserialize<'de> for Timeout {
+impl<'de> Deserialize<'de> for TimeZone {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let value = Cow::<str>::deserialize(deserializer)?;
-        humantime::parse_duration(&value)
-            .map(Timeout)
-            .map_err(de::Error::custom)
+
+        if value.eq_ignore_ascii_case("local") {
+            return Ok(TimeZone::Local);
+        }
+
+        if value.eq_ignore_ascii_case("utc") {
+            return Ok(TimeZone::Utc);
+        }
+
+        let invalid = || de::Error::custom(format!("invalid timezone: {}", value));
+
+        let (sign, digits) = match value.as_bytes().first() {
+            Some(b'+') => (1, value[1..].replace(':', "")),
+            Some(b'-') => (-1, value[1..].replace(':', "")),
+            _ => return Err(invalid()),
+        };
+
+        if !matches!(digits.len(), 2 | 4) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let hours: i32 = digits[..2].parse().map_err(|_| invalid())?;
+        let minutes: i32 = digits[2..].parse().unwrap_or(0);
+
+        if hours > 23 || minutes > 59 {
+            return Err(invalid());
+        }
+
+        Ok(TimeZone::Offset(sign * (hours * 3600 + minutes * 60)))
     }
 }
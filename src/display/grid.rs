@@ -4,7 +4,9 @@
 //! the definitions for the info boxes.
 
 use ansi_term::Style;
-use unicode_width::UnicodeWidthStr;
+
+use super::width::str_width;
+use crate::config::AmbiguousWidth;
 
 /// Data to be written to the screen.
 pub struct Screen {
@@ -20,20 +22,23 @@ pub struct Column {
     pub(super) width: usize,
     pub(super) height: usize,
     pub(super) has_files: bool,
+    pub(super) separator: bool,
     pub(super) style: Option<Style>,
     pub(super) rows: Vec<Row>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct Row {
     pub(super) spans: Vec<Span>,
     pub(super) width: usize,
+    ambiguous_width: AmbiguousWidth,
 }
 
 #[derive(Debug)]
 pub struct Span {
     pub text: Box<str>,
     pub style: Option<Style>,
+    pub link: Option<Box<str>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -49,6 +54,7 @@ impl Column {
             width: 0,
             height: 0,
             has_files,
+            separator: false,
             style: None,
             rows: Vec::new(),
         }
@@ -61,15 +67,29 @@ impl Column {
             width,
             height,
             has_files: false,
+            separator: false,
             style,
             rows: Vec::new(),
         }
     }
 
+    /// Creates a padding column between two groups. The next columns can be
+    /// moved to another band if they don't fit in the terminal.
+    pub fn separator(width: usize) -> Column {
+        Column {
+            separator: true,
+            ..Column::padding(width, 0, None)
+        }
+    }
+
     pub fn height(&self) -> usize {
         self.height
     }
 
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
     pub fn align_right(&mut self) {
         self.align = Align::Right;
     }
@@ -94,8 +114,14 @@ impl Column {
 }
 
 impl Row {
-    pub fn new() -> Row {
-        Row::default()
+    /// Creates an empty row. The width of the East Asian ambiguous characters
+    /// in its text is `ambiguous_width`.
+    pub fn new(ambiguous_width: AmbiguousWidth) -> Row {
+        Row {
+            spans: Vec::new(),
+            width: 0,
+            ambiguous_width,
+        }
     }
 
     pub fn add_text<S>(&mut self, text: S, style: Option<Style>)
@@ -103,8 +129,23 @@ impl Row {
         S: Into<Box<str>>,
     {
         let text = text.into();
-        self.width += text.width();
-        self.spans.push(Span { text, style })
+        self.width += str_width(&text, self.ambiguous_width);
+        self.spans.push(Span {
+            text,
+            style,
+            link: None,
+        })
+    }
+
+    /// Like `add_text`, but the text is a hyperlink to `link`.
+    pub fn add_link<S>(&mut self, text: S, style: Option<Style>, link: &str)
+    where
+        S: Into<Box<str>>,
+    {
+        self.add_text(text, style);
+        if let Some(span) = self.spans.last_mut() {
+            span.link = Some(link.into());
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -114,7 +155,7 @@ impl Row {
 
 #[test]
 fn compute_row_width() {
-    let mut row = Row::new();
+    let mut row = Row::new(AmbiguousWidth::Narrow);
     row.add_text("α = 0", None);
     row.add_text("(＠)", Some(Style::default().bold()));
 
@@ -5,17 +5,21 @@
 mod grid;
 mod strings;
 pub mod styles;
+mod width;
 
-use crate::config::{self, ColorsWhen};
+use crate::config::{self, ColorsWhen, Overflow};
 use std::env;
 use std::io::{self, Write};
 use std::num::NonZeroUsize;
 
 pub use grid::{Column, Row, Screen, Span};
-pub use strings::QuotedString;
+pub use strings::{QuotedString, ELLIPSIS};
+
+/// OSC 8 sequence to close a hyperlink.
+const HYPERLINK_END: &str = "\x1b]8;;\x1b\\";
 
 /// Terminal width when the value can't be read from the TTY.
-const DEFAULT_WIDTH: usize = 80;
+pub const DEFAULT_WIDTH: usize = 80;
 
 /// Format the generated columns and write them to `output`.
 pub fn print(mut output: impl Write, screen: Screen, config: &config::Root) -> io::Result<()> {
@@ -26,11 +30,20 @@ pub fn print(mut output: impl Write, screen: Screen, config: &config::Root) -> i
         print_columns(&mut output, width, &header, config)?;
     }
 
-    // Main columns.
-    let mut columns = screen.columns;
+    // Main columns. If they don't fit in the terminal, the groups are moved
+    // to multiple bands, unless `grid.overflow` is `drop`. The info column is
+    // added to the first band, so its width is reserved.
+    let info_width = screen.info_column.as_ref().map(|c| c.width).unwrap_or(0);
+    let mut bands = match (width, config.grid.overflow) {
+        (Some(width), None | Some(Overflow::Wrap)) => {
+            split_bands(screen.columns, width.get(), info_width)
+        }
+        _ => vec![screen.columns],
+    };
 
     if let Some(info_column) = screen.info_column {
         let width = width.map(|w| w.get()).unwrap_or(DEFAULT_WIDTH);
+        let columns = &mut bands[0];
         let columns_width: usize = columns.iter().map(|c| c.width).sum();
         if let Some(padding) = width.checked_sub(columns_width + info_column.width) {
             columns.push(Column::padding(padding, 0, None));
@@ -38,7 +51,64 @@ pub fn print(mut output: impl Write, screen: Screen, config: &config::Root) -> i
         }
     }
 
-    print_columns(&mut output, width, &columns, config)
+    for (index, band) in bands.iter().enumerate() {
+        if index > 0 {
+            writeln!(&mut output)?;
+        }
+
+        print_columns(&mut output, width, band, config)?;
+    }
+
+    Ok(())
+}
+
+/// Split the columns in bands that fit in `max_width`. In the first band,
+/// `reserved` columns are kept free.
+///
+/// Columns are moved to the next band only at separators, so the columns of a
+/// group are always in the same band. The separator before the first group of
+/// a band is discarded.
+fn split_bands(columns: Vec<Column>, max_width: usize, reserved: usize) -> Vec<Vec<Column>> {
+    let mut bands = vec![Vec::new()];
+    let mut band_width = 0;
+
+    let mut columns = columns.into_iter().peekable();
+    while let Some(first) = columns.next() {
+        let (separator, mut group) = if first.separator {
+            (Some(first), Vec::new())
+        } else {
+            (None, vec![first])
+        };
+
+        while let Some(column) = columns.next_if(|c| !c.separator) {
+            group.push(column);
+        }
+
+        let group_width: usize = group.iter().map(|c| c.width).sum();
+        let separator_width = separator.as_ref().map(|s| s.width).unwrap_or(0);
+
+        let band_max_width = if bands.len() == 1 {
+            max_width.saturating_sub(reserved)
+        } else {
+            max_width
+        };
+
+        if band_width > 0 && band_width + separator_width + group_width > band_max_width {
+            bands.push(Vec::new());
+            band_width = 0;
+        }
+
+        let band = bands.last_mut().unwrap();
+        if let Some(separator) = separator.filter(|_| band_width > 0) {
+            band_width += separator.width;
+            band.push(separator);
+        }
+
+        band_width += group_width;
+        band.extend(group);
+    }
+
+    bands
 }
 
 fn compute_header(
@@ -138,9 +208,25 @@ fn print_columns(
                         padding!(column.width.saturating_sub(row.width));
                     }
 
-                    for Span { text, style } in &row.spans {
+                    // Consecutive spans with the same target are in the
+                    // same hyperlink.
+                    let mut current_link = None;
+
+                    for Span { text, style, link } in &row.spans {
                         let style = styles::combine_opt(column.style, *style);
 
+                        if use_colors && current_link != link.as_deref() {
+                            if current_link.is_some() {
+                                write!(&mut output, "{}", HYPERLINK_END)?;
+                            }
+
+                            if let Some(link) = link {
+                                write!(&mut output, "\x1b]8;;{}\x1b\\", link)?;
+                            }
+
+                            current_link = link.as_deref();
+                        }
+
                         if use_colors {
                             if let Some(style) = style {
                                 write!(&mut output, "{}", style.prefix())?;
@@ -156,6 +242,10 @@ fn print_columns(
                         }
                     }
 
+                    if current_link.is_some() {
+                        write!(&mut output, "{}", HYPERLINK_END)?;
+                    }
+
                     if column.align == grid::Align::Left {
                         padding!(column.width.saturating_sub(row.width));
                     }
@@ -188,7 +278,7 @@ fn print_columns(
 /// 1. It checks the `COLUMNS` environment variable.
 /// 2. Then, it queries the TTY for the window size.
 /// 3. If none of the above works, returns `None`.
-fn terminal_width() -> Option<NonZeroUsize> {
+pub fn terminal_width() -> Option<NonZeroUsize> {
     if let Ok(Ok(w)) = env::var("COLUMNS").map(|c| c.parse()) {
         return NonZeroUsize::new(w);
     }
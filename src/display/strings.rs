@@ -11,7 +11,14 @@ use std::num::NonZeroUsize;
 #[cfg(unix)]
 use std::os::unix::ffi::OsStrExt;
 
-use unicode_width::UnicodeWidthChar;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::width::cluster_width;
+
+use crate::config::{AmbiguousWidth, QuotingStyle, Truncation};
+
+/// Text added to the truncated strings.
+pub const ELLIPSIS: &str = "…";
 
 /// Contains a [`OsStr`] that can be formatted as a Unicode string.
 ///
@@ -21,14 +28,23 @@ use unicode_width::UnicodeWidthChar;
 pub struct QuotedString<'a> {
     string: &'a OsStr,
     max_width: Option<NonZeroUsize>,
+    quoting: QuotingStyle,
+    ambiguous_width: AmbiguousWidth,
     truncated: Cell<bool>,
 }
 
 impl QuotedString<'_> {
-    pub fn new(string: &OsStr, max_width: Option<NonZeroUsize>) -> QuotedString {
+    pub fn new(
+        string: &OsStr,
+        max_width: Option<NonZeroUsize>,
+        quoting: QuotingStyle,
+        ambiguous_width: AmbiguousWidth,
+    ) -> QuotedString {
         QuotedString {
             string,
             max_width,
+            quoting,
+            ambiguous_width,
             truncated: Cell::new(false),
         }
     }
@@ -40,7 +56,12 @@ impl QuotedString<'_> {
     ///
     /// ```
     /// use std::num::NonZeroUsize;
-    /// let qs = QuotedString::new(OsStr::new("abcd"), NonZeroUsize::new(3));
+    /// let qs = QuotedString::new(
+    ///     OsStr::new("abcd"),
+    ///     NonZeroUsize::new(3),
+    ///     QuotingStyle::Escape,
+    ///     AmbiguousWidth::Narrow,
+    /// );
     ///
     /// let mut s = qs.to_string();
     /// if qs.is_truncated() {
@@ -51,13 +72,110 @@ impl QuotedString<'_> {
         self.truncated.get()
     }
 
+    /// Returns the text before and after the ellipsis, using the truncation
+    /// strategy. The second element is `None` if the string is not truncated.
+    ///
+    /// With `End`, the text after the ellipsis is always empty.
+    pub fn split(&self, truncation: Truncation) -> (String, Option<String>) {
+        let head = self.to_string();
+        if !self.is_truncated() {
+            return (head, None);
+        }
+
+        let max_width = match self.max_width {
+            Some(w) => w.get(),
+            None => return (head, None),
+        };
+
+        // Collect the units of the string, with their widths.
+        let mut units = Vec::new();
+        let _ = self.write_units(|unit, width| {
+            units.push((unit.to_owned(), width));
+            Ok(true)
+        });
+
+        // Some columns are reserved for the ellipsis.
+        let budget = max_width.saturating_sub(self.ellipsis_width());
+
+        let take_head = |budget: usize| {
+            let mut width = 0;
+            units
+                .iter()
+                .take_while(|(_, w)| {
+                    width += w;
+                    width <= budget
+                })
+                .count()
+        };
+
+        let (head_len, tail_start) = match truncation {
+            Truncation::End => return (head, Some(String::new())),
+
+            Truncation::Middle => {
+                let head_len = take_head(budget - budget / 2);
+                let head_width: usize = units[..head_len].iter().map(|(_, w)| w).sum();
+
+                let mut tail_width = 0;
+                let tail_len = units[head_len..]
+                    .iter()
+                    .rev()
+                    .take_while(|(_, w)| {
+                        tail_width += w;
+                        head_width + tail_width <= budget
+                    })
+                    .count();
+
+                (head_len, units.len() - tail_len)
+            }
+
+            Truncation::KeepExtension => {
+                // The extension starts at the last dot, unless the dot is
+                // the first character (like in hidden files).
+                let dot = units.iter().rposition(|(u, _)| u == ".").filter(|&d| d > 0);
+                let ext_width = |d: usize| units[d..].iter().map(|(_, w)| w).sum::<usize>();
+
+                match dot {
+                    Some(dot) if ext_width(dot) < budget => {
+                        (take_head(budget - ext_width(dot)), dot)
+                    }
+
+                    _ => return (head, Some(String::new())),
+                }
+            }
+        };
+
+        let concat = |units: &[(String, usize)]| units.iter().map(|(u, _)| &u[..]).collect();
+        (
+            concat(&units[..head_len]),
+            Some(concat(&units[tail_start..])),
+        )
+    }
+
+    /// Returns the width of the string, without truncation.
+    fn width(&self) -> usize {
+        let mut width = 0;
+        let _ = self.write_units(|_, unit_width| {
+            width += unit_width;
+            Ok(true)
+        });
+
+        width
+    }
+
+    /// Width of the ellipsis added to the truncated strings.
+    fn ellipsis_width(&self) -> usize {
+        cluster_width(ELLIPSIS, self.ambiguous_width)
+    }
+
     /// Implementation for ASCII-only strings with no control characters.
     #[cfg(unix)]
     #[inline(always)]
     fn try_write_unquoted(&self, fmt: &mut fmt::Formatter) -> Result<bool, fmt::Error> {
         let bytes = self.string.as_bytes();
 
-        if !bytes.iter().all(|b| (20..128).contains(b)) {
+        if !matches!(self.quoting, QuotingStyle::Literal | QuotingStyle::Escape)
+            || !bytes.iter().all(|b| (0x20..0x7F).contains(b))
+        {
             return Ok(false);
         }
 
@@ -66,7 +184,8 @@ impl QuotedString<'_> {
 
         match self.max_width {
             Some(max_width) if max_width.get() < bytes.len() => {
-                let s = unsafe { std::str::from_utf8_unchecked(&bytes[..max_width.get() - 1]) };
+                let len = max_width.get().saturating_sub(self.ellipsis_width());
+                let s = unsafe { std::str::from_utf8_unchecked(&bytes[..len]) };
                 fmt.write_str(s)?;
                 self.truncated.set(true);
             }
@@ -78,41 +197,130 @@ impl QuotedString<'_> {
 
         Ok(true)
     }
-}
 
-impl fmt::Display for QuotedString<'_> {
-    #[cfg(unix)]
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        if self.try_write_unquoted(fmt)? {
-            return Ok(());
+    /// Send every unit of the string (a grapheme cluster, an escape sequence,
+    /// or a quote) to `f`, with its width. Units depend on the quoting style.
+    ///
+    /// It stops when `f` returns `false`.
+    fn write_units<F>(&self, mut f: F) -> fmt::Result
+    where
+        F: FnMut(&str, usize) -> Result<bool, fmt::Error>,
+    {
+        macro_rules! unit {
+            ($unit:expr, $width:expr) => {
+                if !f($unit, $width)? {
+                    return Ok(false);
+                }
+            };
+
+            ($unit:expr) => {{
+                let unit = $unit;
+                unit!(&unit, unit.len())
+            }};
+        }
+
+        let quotes = match self.quoting {
+            QuotingStyle::Literal | QuotingStyle::Escape => None,
+
+            QuotingStyle::Shell => {
+                let mut needs_quotes = false;
+                self.pieces(|piece| {
+                    needs_quotes = match piece {
+                        Piece::Text(text) => text.chars().any(is_shell_special),
+                        Piece::Byte(_) => true,
+                    };
+
+                    Ok(!needs_quotes)
+                })?;
+
+                Some("'").filter(|_| needs_quotes)
+            }
+
+            QuotingStyle::ShellAlways => Some("'"),
+
+            QuotingStyle::C => Some("\""),
+        };
+
+        if let Some(quote) = quotes {
+            if !f(quote, 1)? {
+                return Ok(());
+            }
+        }
+
+        let complete = self.pieces(|piece| {
+            match (self.quoting, piece) {
+                (_, Piece::Text(text)) if quotes.is_none() => {
+                    unit!(text, cluster_width(text, self.ambiguous_width));
+                }
+
+                (QuotingStyle::Literal, Piece::Byte(_)) => unit!("?", 1),
+
+                (QuotingStyle::Escape, Piece::Byte(byte)) => unit!(format!("\\x{:02X}", byte)),
+
+                (QuotingStyle::C, Piece::Text("\"")) => unit!("\\\"", 2),
+
+                (QuotingStyle::C, Piece::Text("\\")) => unit!("\\\\", 2),
+
+                (QuotingStyle::C, Piece::Byte(byte)) => match c_escape(byte) {
+                    Some(chr) => unit!(format!("\\{}", chr)),
+                    None => unit!(format!("\\{:03o}", byte)),
+                },
+
+                (QuotingStyle::C, Piece::Text(text)) => {
+                    unit!(text, cluster_width(text, self.ambiguous_width))
+                }
+
+                // Single quotes can't be escaped in a single-quoted string,
+                // so the string is closed, and reopened after the quote.
+                (_, Piece::Text("'")) => unit!("'\\''", 4),
+
+                (_, Piece::Byte(byte)) => unit!(format!("'$'\\x{:02X}''", byte)),
+
+                (_, Piece::Text(text)) => unit!(text, cluster_width(text, self.ambiguous_width)),
+            }
+
+            Ok(true)
+        })?;
+
+        if let (Some(quote), true) = (quotes, complete) {
+            f(quote, 1)?;
         }
 
+        Ok(())
+    }
+
+    /// Split the string in grapheme clusters and bytes. Control characters
+    /// (including `DEL`) and invalid UTF-8 sequences are sent as bytes.
+    ///
+    /// It stops when `f` returns `false`, and returns `false` in that case.
+    #[cfg(unix)]
+    fn pieces<F>(&self, mut f: F) -> Result<bool, fmt::Error>
+    where
+        F: FnMut(Piece) -> Result<bool, fmt::Error>,
+    {
         let mut bytes = self.string.as_bytes();
-        let mut width = self.max_width;
-
-        macro_rules! next_char {
-            ($width:expr) => {{
-                if let Some(w) = width.as_mut() {
-                    let char_width = $width;
-                    if w.get() <= char_width {
-                        self.truncated.set(true);
-                        return Ok(());
-                    }
 
-                    width = NonZeroUsize::new(w.get() - char_width);
+        macro_rules! piece {
+            ($piece:expr) => {
+                if !f($piece)? {
+                    return Ok(false);
                 }
-            }};
+            };
         }
 
         macro_rules! write_str {
             ($str:expr) => {
-                for chr in $str.chars() {
-                    if chr < ' ' {
-                        next_char!(4);
-                        write!(fmt, "\\x{:02X}", chr as u32)?;
+                for cluster in $str.graphemes(true) {
+                    if cluster.chars().any(is_control) {
+                        for (idx, chr) in cluster.char_indices() {
+                            if is_control(chr) {
+                                piece!(Piece::Byte(chr as u8));
+                            } else {
+                                piece!(Piece::Text(&cluster[idx..idx + chr.len_utf8()]));
+                            }
+                        }
                     } else {
-                        next_char!(chr.width().unwrap_or(0));
-                        write!(fmt, "{}", chr)?;
+                        piece!(Piece::Text(cluster));
                     }
                 }
             };
@@ -122,7 +330,7 @@ impl fmt::Display for QuotedString<'_> {
             match std::str::from_utf8(bytes) {
                 Ok(s) => {
                     write_str!(s);
-                    return Ok(());
+                    return Ok(true);
                 }
 
                 Err(e) => {
@@ -138,8 +346,7 @@ impl fmt::Display for QuotedString<'_> {
                     };
 
                     for byte in invalid {
-                        next_char!(4);
-                        write!(fmt, "\\x{:02X}", *byte)?;
+                        piece!(Piece::Byte(*byte));
                     }
 
                     bytes = &after_valid[invalid.len()..];
@@ -149,30 +356,83 @@ impl fmt::Display for QuotedString<'_> {
     }
 
     #[cfg(not(unix))]
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fn pieces<F>(&self, mut f: F) -> Result<bool, fmt::Error>
+    where
+        F: FnMut(Piece) -> Result<bool, fmt::Error>,
+    {
         use std::path::Path;
 
-        let display = Path::new(self.string).display();
+        for cluster in Path::new(self.string).display().to_string().graphemes(true) {
+            if !f(Piece::Text(cluster))? {
+                return Ok(false);
+            }
+        }
 
-        match self.max_width {
-            Some(max_width) => {
-                let mut width = 0;
-                for chr in display.to_string().chars() {
-                    width += chr.width().unwrap_or(1);
-
-                    if width >= max_width.get() {
-                        self.truncated.set(true);
-                        return Ok(());
-                    }
+        Ok(true)
+    }
+}
 
-                    write!(fmt, "{}", chr)?;
-                }
+/// Returns `true` if the character is an ASCII control character.
+#[cfg(unix)]
+fn is_control(chr: char) -> bool {
+    chr < ' ' || chr == '\x7F'
+}
 
-                Ok(())
-            }
+/// Part of a string, generated by `QuotedString::pieces`.
+enum Piece<'a> {
+    Text(&'a str),
+
+    /// Only generated in Unix, where the strings can contain any byte.
+    #[cfg_attr(not(unix), allow(dead_code))]
+    Byte(u8),
+}
+
+/// Returns `true` if the character requires quotes in a shell.
+fn is_shell_special(chr: char) -> bool {
+    chr.is_ascii() && !chr.is_ascii_alphanumeric() && !"%+,-./:@_".contains(chr)
+}
+
+/// Character for the escape sequence of a control character in a C string.
+fn c_escape(byte: u8) -> Option<char> {
+    Some(match byte {
+        0x07 => 'a',
+        0x08 => 'b',
+        b'\t' => 't',
+        b'\n' => 'n',
+        0x0B => 'v',
+        0x0C => 'f',
+        b'\r' => 'r',
+        _ => return None,
+    })
+}
 
-            None => write!(fmt, "{}", display),
+impl fmt::Display for QuotedString<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(unix)]
+        if self.try_write_unquoted(fmt)? {
+            return Ok(());
         }
+
+        let max_width = match self.max_width {
+            Some(max_width) if self.width() > max_width.get() => {
+                max_width.get().saturating_sub(self.ellipsis_width())
+            }
+
+            _ => return self.write_units(|unit, _| fmt.write_str(unit).map(|_| true)),
+        };
+
+        self.truncated.set(true);
+
+        let mut width = 0;
+        self.write_units(|unit, unit_width| {
+            width += unit_width;
+            if width > max_width {
+                return Ok(false);
+            }
+
+            fmt.write_str(unit)?;
+            Ok(true)
+        })
     }
 }
 
@@ -188,7 +448,12 @@ fn quote_strings() {
         };
 
         ($string:expr, $width:expr, $expected:expr, $truncated:expr) => {
-            let qs = QuotedString::new(OsStr::from_bytes(&$string[..]), NonZeroUsize::new($width));
+            let qs = QuotedString::new(
+                OsStr::from_bytes(&$string[..]),
+                NonZeroUsize::new($width),
+                QuotingStyle::Escape,
+                AmbiguousWidth::Narrow,
+            );
             assert_eq!(format!("{}", qs), $expected);
             assert_eq!(qs.is_truncated(), $truncated);
         };
@@ -202,4 +467,90 @@ fn quote_strings() {
     check!(b"aaa", 3, "aaa");
     check!(b"bbbbb", 3, "bb", true);
     check!(b"\xCE\xB1 \xEF\xBC", 3, "α ", true);
+    check!(b"\xCE\xB1\xCE\xB1\xCE\xB1", 3, "ααα");
+    check!(b"\xCE\xB1\xCE\xB1\xCE\xB1", 2, "α", true);
+}
+
+#[cfg(unix)]
+#[test]
+fn truncation_strategies() {
+    macro_rules! check {
+        ($string:expr, $width:expr, $truncation:ident, $head:expr, $tail:expr) => {
+            let qs = QuotedString::new(
+                OsStr::new($string),
+                NonZeroUsize::new($width),
+                QuotingStyle::Escape,
+                AmbiguousWidth::Narrow,
+            );
+            let (head, tail) = qs.split(Truncation::$truncation);
+            assert_eq!((head.as_str(), tail.as_deref()), ($head, $tail));
+        };
+    }
+
+    check!("report.pdf", 20, Middle, "report.pdf", None);
+    check!("report.pdf", 20, KeepExtension, "report.pdf", None);
+
+    check!("very_long_report.pdf", 10, End, "very_long", Some(""));
+    check!("very_long_report.pdf", 10, Middle, "very_", Some(".pdf"));
+    check!(
+        "very_long_report.pdf",
+        10,
+        KeepExtension,
+        "very_",
+        Some(".pdf")
+    );
+    check!("very_long_report.pdf", 11, Middle, "very_", Some("t.pdf"));
+
+    // Double-width characters.
+    check!("ＡＢＣＤＥＦ.txt", 10, Middle, "ＡＢ", Some(".txt"));
+    check!("ＡＢＣＤＥＦ.txt", 10, KeepExtension, "ＡＢ", Some(".txt"));
+
+    // Escape sequences are not split.
+    check!("a\nbcdefgh.txt", 10, KeepExtension, "a\\x0A", Some(".txt"));
+
+    // Hidden files and long extensions.
+    check!(
+        ".a_long_hidden_file",
+        10,
+        KeepExtension,
+        ".a_long_h",
+        Some("")
+    );
+    check!("file.extension", 8, KeepExtension, "file.ex", Some(""));
+}
+
+#[cfg(unix)]
+#[test]
+fn quoting_styles() {
+    use std::os::unix::ffi::OsStrExt;
+
+    macro_rules! check {
+        ($string:expr, $style:ident, $expected:expr) => {
+            let qs = QuotedString::new(
+                OsStr::from_bytes(&$string[..]),
+                None,
+                QuotingStyle::$style,
+                AmbiguousWidth::Narrow,
+            );
+            assert_eq!(format!("{}", qs), $expected);
+        };
+    }
+
+    check!(b"a\nb\xFF", Literal, "a?b?");
+    check!(b"a\nb\xFF", Escape, "a\\x0Ab\\xFF");
+    check!(b"a\x1B[0m\x7F", Literal, "a?[0m?");
+    check!(b"a\x1B[0m\x7F", Escape, "a\\x1B[0m\\x7F");
+    check!(b"a\x1Bb\x7F", Shell, "'a'$'\\x1B''b'$'\\x7F'''");
+    check!(b"a\x1Bb\x7F", C, "\"a\\033b\\177\"");
+
+    check!(b"file.txt", Shell, "file.txt");
+    check!(b"two words", Shell, "'two words'");
+    check!(b"it's", Shell, "'it'\\''s'");
+    check!(b"a\nb", Shell, "'a'$'\\x0A''b'");
+    check!(b"file.txt", ShellAlways, "'file.txt'");
+    check!("αβ".as_bytes(), Shell, "αβ");
+
+    check!(b"file.txt", C, "\"file.txt\"");
+    check!(b"a\"b\\c'd", C, "\"a\\\"b\\\\c'd\"");
+    check!(b"a\tb\x01\xFF", C, "\"a\\tb\\001\\377\"");
 }
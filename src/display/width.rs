@@ -0,0 +1,87 @@
+//! This module computes the width of the text in the terminal.
+//!
+//! The width is computed for every grapheme cluster, so emoji sequences, flags
+//! and combining marks are measured like a single character.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+use crate::config::AmbiguousWidth;
+
+/// Emoji presentation selector.
+const VARIATION_SELECTOR_16: char = '\u{FE0F}';
+
+/// Returns the width of a string.
+pub fn str_width(s: &str, ambiguous: AmbiguousWidth) -> usize {
+    s.graphemes(true).map(|c| cluster_width(c, ambiguous)).sum()
+}
+
+/// Returns the width of a single grapheme cluster. `ambiguous` is the width of
+/// the East Asian ambiguous characters.
+pub fn cluster_width(cluster: &str, ambiguous: AmbiguousWidth) -> usize {
+    let char_width = |c: char| {
+        match ambiguous {
+            AmbiguousWidth::Narrow => c.width(),
+            AmbiguousWidth::Wide => c.width_cjk(),
+        }
+        .unwrap_or(0)
+    };
+
+    // Emoji presentations and flags (pairs of regional indicators) use two
+    // columns.
+    let mut chars = cluster.chars();
+    let is_flag = matches!(
+        (chars.next(), chars.next()),
+        (Some(a), Some(b)) if is_regional_indicator(a) && is_regional_indicator(b)
+    );
+
+    if is_flag || cluster.contains(VARIATION_SELECTOR_16) {
+        return 2;
+    }
+
+    // In any other cluster, only the widest character is visible (like the
+    // base character of a combining sequence, or the emojis joined by a ZWJ).
+    cluster.chars().map(char_width).max().unwrap_or(0)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+#[test]
+fn grapheme_widths() {
+    macro_rules! check {
+        ($s:expr, $width:expr) => {
+            assert_eq!(
+                $s.graphemes(true)
+                    .map(|c| cluster_width(c, AmbiguousWidth::Narrow))
+                    .sum::<usize>(),
+                $width
+            );
+        };
+    }
+
+    check!("abc", 3);
+    check!("α = 0", 5);
+    check!("Ａ", 2);
+
+    // Combining marks.
+    check!("e\u{301}", 1);
+    check!("n\u{303}o\u{308}", 2);
+
+    // Flags.
+    check!("\u{1F1EA}\u{1F1F8}", 2);
+    check!("\u{1F1EA}\u{1F1F8}\u{1F1EB}\u{1F1F7}", 4);
+
+    // ZWJ sequences.
+    check!("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}", 2);
+    check!("\u{1F3F3}\u{FE0F}\u{200D}\u{1F308}", 2);
+
+    // Emoji presentation.
+    check!("\u{2764}\u{FE0F}", 2);
+    check!("1\u{FE0F}\u{20E3}", 2);
+
+    // East Asian ambiguous characters.
+    assert_eq!(cluster_width("±", AmbiguousWidth::Narrow), 1);
+    assert_eq!(cluster_width("±", AmbiguousWidth::Wide), 2);
+}
@@ -9,10 +9,13 @@
 use crate::config;
 use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
 use std::{fs, io};
 
+use super::images::ImageReader;
+use super::markers::MarkersReader;
 use super::treereader::TreeReader;
-use super::{gitdiff, matchers, sorting};
+use super::{commands, duplicates, gitdiff, matchers, sorting, split};
 use super::{Analysis, File, FilesGroup};
 
 /// Read a path and analyze it.
@@ -23,7 +26,10 @@ pub(super) fn analyze_path<'a>(
     let mut variables = HashMap::new();
 
     // Run the collectors to get git and disk usage data.
-    let tree_reader = TreeReader::new(config);
+    let threadpool = threadpool::Builder::new().build();
+    let tree_reader = TreeReader::new(config, &threadpool);
+    let markers_reader = MarkersReader::new(config, &threadpool);
+    let image_reader = ImageReader::new(config, &threadpool);
     let diff_stats = gitdiff::collect(path, config);
 
     let mut disk_usage_files = 0;
@@ -34,42 +40,91 @@ pub(super) fn analyze_path<'a>(
         .iter()
         .map(|c| FilesGroup {
             column: c,
+            label: c.label.clone(),
             files: Vec::new(),
         })
         .collect::<Vec<_>>();
 
-    for entry in fs::read_dir(path)? {
-        let (path, file_name, metadata) = match entry.map(|e| (e.metadata(), e)) {
-            Ok((Ok(m), e)) => (e.path(), e.file_name(), m),
-            _ => continue,
-        };
+    // Entries are read before the analysis, because the command collectors
+    // need the file names.
+    let entries: Vec<_> = fs::read_dir(path)?
+        .filter_map(|entry| match entry.map(|e| (e.metadata(), e)) {
+            Ok((Ok(m), e)) => Some((e.path(), e.file_name(), m)),
+            _ => None,
+        })
+        .collect();
+
+    let commands = commands::collect(
+        path,
+        entries.iter().map(|(_, name, _)| name.clone()).collect(),
+        config,
+        &threadpool,
+    );
+
+    let duplicates = duplicates::find(&entries, config, &threadpool);
+
+    let mut files: Vec<_> = entries
+        .into_iter()
+        .map(|(path, file_name, metadata)| {
+            if metadata.is_file() {
+                disk_usage_files += metadata.len();
+            }
 
-        if metadata.is_file() {
-            disk_usage_files += metadata.len();
-        }
+            let tree_info = tree_reader.as_ref().and_then(|duc| {
+                if metadata.is_dir() {
+                    Some(duc.read_info(&path))
+                } else {
+                    None
+                }
+            });
 
-        let tree_info = tree_reader.as_ref().and_then(|duc| {
-            if metadata.is_dir() {
-                Some(duc.read_info(&path))
-            } else {
-                None
+            let markers = markers_reader
+                .as_ref()
+                .and_then(|mr| mr.count(&path, &metadata));
+
+            let git_changes = diff_stats.as_ref().and_then(|c| c.get(&file_name)).copied();
+
+            let image_size = image_reader
+                .as_ref()
+                .and_then(|ir| ir.read(&path, &metadata));
+
+            File {
+                file_name,
+                metadata,
+                tree_info,
+                markers,
+                duplicate: None,
+                image_size,
+                git_changes,
+                command_values: commands::Values::new(),
             }
-        });
+        })
+        .collect();
+
+    // Results from the commands and the duplicates are collected after
+    // launching all jobs, so they don't delay the other collectors.
+    let mut command_results = commands.wait();
+    let mut duplicates = duplicates.wait();
+    for file in &mut files {
+        file.command_values = command_results.remove(&file.file_name).unwrap_or_default();
+        file.duplicate = duplicates.remove(&file.file_name);
+    }
 
-        let git_changes = diff_stats.as_ref().and_then(|c| c.get(&file_name));
-        let file_name_path = Path::new(&file_name);
+    // The total of markers is computed after launching all jobs.
+    let markers = markers_reader.map(|_| {
+        files
+            .iter()
+            .filter_map(|f| f.markers.as_ref().and_then(|m| m.get()))
+            .sum()
+    });
+
+    for file in files {
+        let file = Rc::new(file);
 
         // Find variables to track this entry.
         if let Some(info) = &config.info {
             for (var_name, matchers) in &info.variables {
-                if matchers::is_match(
-                    file_name_path,
-                    &metadata,
-                    tree_info.as_ref(),
-                    git_changes,
-                    true,
-                    matchers,
-                ) {
+                if matchers::is_match(&file, true, matchers) {
                     *variables.entry(&**var_name).or_default() += 1;
                 }
             }
@@ -77,37 +132,23 @@ pub(super) fn analyze_path<'a>(
 
         // Find a group for this directory entry.
         for group in &mut groups {
-            if matchers::is_match(
-                file_name_path,
-                &metadata,
-                tree_info.as_ref(),
-                git_changes,
-                true,
-                &group.column.exclude,
-            ) {
+            if matchers::is_match(&file, true, &group.column.exclude) {
                 continue;
             }
 
-            if matchers::is_match(
-                file_name_path,
-                &metadata,
-                tree_info.as_ref(),
-                git_changes,
-                group.column.include_hidden,
-                &group.column.matchers,
-            ) {
-                group.files.push(File {
-                    file_name,
-                    metadata,
-                    tree_info,
-                    git_changes: git_changes.copied(),
-                });
-
-                break;
+            if matchers::is_match(&file, group.column.include_hidden, &group.column.matchers) {
+                group.files.push(Rc::clone(&file));
+
+                if group.column.exclusive {
+                    break;
+                }
             }
         }
     }
 
+    // Replace the columns with a `split` setting with the generated ones.
+    let mut groups = split::split_groups(groups, config);
+
     // Sort the contents of every column.
     for group in &mut groups {
         sorting::sort(group);
@@ -119,5 +160,6 @@ pub(super) fn analyze_path<'a>(
         variables,
         changes: diff_stats.map(|ds| ds.values().sum()),
         disk_usage_files,
+        markers,
     })
 }
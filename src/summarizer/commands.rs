@@ -0,0 +1,308 @@
+//! This module implements a collector to get values from external programs,
+//! defined in the `collector.commands` section of the configuration.
+//!
+//! Programs are executed in the summarized directory. File names are written
+//! to their standard input, every one followed by a NUL byte. The program is
+//! expected to write one result per file, every one in a separate line.
+//!
+//! There are two modes to execute a program:
+//!
+//! * `directory`: the program is executed once, and it receives all entries
+//!   of the directory. The lines in its output are associated with the file
+//!   names in the same order. An empty line means that there is no result for
+//!   that file.
+//!
+//! * `entry`: the program is executed once for every entry, and the first line
+//!   of its output is the result for that entry.
+//!
+//! The exit status of the programs is ignored.
+//!
+//! Every command runs in the thread pool of the collectors. When the timeout
+//! expires, the running program is killed, no more programs are launched, and
+//! the results are discarded.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Instant;
+use std::{mem, thread};
+
+use crate::config::{self, CommandMode};
+use threadpool::ThreadPool;
+
+/// Values collected for a single file. Keys are the names of the commands.
+pub type Values = HashMap<String, String>;
+
+/// Map to associate file names with their values.
+pub type Results = HashMap<OsString, Values>;
+
+/// Programs launched in the thread pool. Their results are collected with
+/// [`PendingCommands::wait`].
+pub struct PendingCommands<'a> {
+    jobs: Vec<PendingJob<'a>>,
+}
+
+/// Channel to receive the results of a program, and its state to kill it
+/// after the deadline.
+struct PendingJob<'a> {
+    name: &'a String,
+    deadline: Option<Instant>,
+    rx: mpsc::Receiver<Vec<(OsString, String)>>,
+    process: Arc<Mutex<Process>>,
+}
+
+/// Execute the programs in `collector.commands` for the entries in
+/// `file_names`.
+pub fn collect<'a>(
+    path: &Path,
+    file_names: Vec<OsString>,
+    config: &'a config::Root,
+    threadpool: &ThreadPool,
+) -> PendingCommands<'a> {
+    let start = Instant::now();
+    let file_names = Arc::new(file_names);
+
+    let jobs = config
+        .collector
+        .commands
+        .iter()
+        .map(|command| {
+            let (tx, rx) = mpsc::channel();
+
+            let deadline = command
+                .timeout
+                .as_ref()
+                .or(config.collector.timeout.as_ref())
+                .map(|t| start + t.0);
+
+            let process = Arc::new(Mutex::new(Process::Idle));
+
+            let job = Job {
+                path: path.to_owned(),
+                program: command.program.clone(),
+                args: command.args.clone(),
+                file_names: Arc::clone(&file_names),
+                process: Arc::clone(&process),
+            };
+
+            let mode = command.mode.unwrap_or(CommandMode::Directory);
+            threadpool.execute(move || {
+                let values = match mode {
+                    CommandMode::Directory => job.run_directory(),
+                    CommandMode::Entry => job.run_entries(deadline),
+                };
+
+                let _ = tx.send(values);
+            });
+
+            PendingJob {
+                name: &command.name,
+                deadline,
+                rx,
+                process,
+            }
+        })
+        .collect();
+
+    PendingCommands { jobs }
+}
+
+impl PendingCommands<'_> {
+    /// Wait for the results of the programs. Programs still running after
+    /// their deadlines are killed.
+    pub fn wait(self) -> Results {
+        let mut results = Results::new();
+
+        for job in self.jobs {
+            let values = match job.deadline {
+                Some(dl) => job
+                    .rx
+                    .recv_timeout(dl.saturating_duration_since(Instant::now()))
+                    .ok(),
+                None => job.rx.recv().ok(),
+            };
+
+            if values.is_none() {
+                cancel(&job.process);
+            }
+
+            for (file_name, value) in values.into_iter().flatten() {
+                results
+                    .entry(file_name)
+                    .or_default()
+                    .insert(job.name.clone(), value);
+            }
+        }
+
+        results
+    }
+}
+
+/// Data to execute a program in a background thread.
+struct Job {
+    path: PathBuf,
+    program: String,
+    args: Vec<String>,
+    file_names: Arc<Vec<OsString>>,
+    process: Arc<Mutex<Process>>,
+}
+
+/// State of the program launched by a job. It is shared with the main thread,
+/// so the program can be killed after the timeout.
+enum Process {
+    Idle,
+    Running(Child),
+    Cancelled,
+}
+
+/// Kill the running program of a job, and prevent it to launch new ones.
+fn cancel(process: &Mutex<Process>) {
+    let mut process = match process.lock() {
+        Ok(p) => p,
+        Err(_) => return,
+    };
+
+    if let Process::Running(mut child) = mem::replace(&mut *process, Process::Cancelled) {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+impl Job {
+    /// Execute the program once, with all file names in its input.
+    fn run_directory(&self) -> Vec<(OsString, String)> {
+        let output = match self.execute(&self.file_names) {
+            Some(o) => o,
+            None => return Vec::new(),
+        };
+
+        self.file_names
+            .iter()
+            .zip(parse_lines(&output))
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(file_name, value)| (file_name.clone(), value))
+            .collect()
+    }
+
+    /// Execute the program once for every file name.
+    ///
+    /// No more programs are launched after `deadline`.
+    fn run_entries(&self, deadline: Option<Instant>) -> Vec<(OsString, String)> {
+        let mut values = Vec::new();
+
+        for file_name in self.file_names.iter() {
+            if matches!(deadline, Some(dl) if dl < Instant::now()) {
+                break;
+            }
+
+            let value = self
+                .execute(std::slice::from_ref(file_name))
+                .and_then(|output| parse_lines(&output).into_iter().next())
+                .filter(|value| !value.is_empty());
+
+            if let Some(value) = value {
+                values.push((file_name.clone(), value));
+            }
+        }
+
+        values
+    }
+
+    /// Launch the program, write the file names to its standard input, and
+    /// returns its output.
+    ///
+    /// Returns `None` if the job is cancelled.
+    fn execute(&self, file_names: &[OsString]) -> Option<Vec<u8>> {
+        // The program is launched while the lock is held, so it can't be
+        // missed by `cancel`.
+        let (stdin, stdout) = {
+            let mut process = self.process.lock().ok()?;
+            if let Process::Cancelled = *process {
+                return None;
+            }
+
+            let mut child = Command::new(&self.program)
+                .args(&self.args)
+                .current_dir(&self.path)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+
+            let pipes = (child.stdin.take(), child.stdout.take());
+            *process = Process::Running(child);
+            pipes
+        };
+
+        let mut input = Vec::new();
+        for file_name in file_names {
+            #[cfg(unix)]
+            {
+                use std::os::unix::ffi::OsStrExt;
+                input.extend_from_slice(file_name.as_bytes());
+            }
+
+            #[cfg(not(unix))]
+            input.extend_from_slice(file_name.to_string_lossy().as_bytes());
+
+            input.push(b'\0');
+        }
+
+        // The input is written in another thread, so the program can't be
+        // blocked if its output is not read.
+        let mut stdin = stdin?;
+        let writer = thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+
+        let mut output = Vec::new();
+        let read = stdout?.read_to_end(&mut output);
+
+        // If the job was cancelled, the program is already killed.
+        let mut process = self.process.lock().ok()?;
+        let mut child = match mem::replace(&mut *process, Process::Idle) {
+            Process::Running(child) => child,
+            state => {
+                *process = state;
+                return None;
+            }
+        };
+
+        drop(process);
+
+        let _ = child.wait();
+        let _ = writer.join();
+
+        read.ok().map(|_| output)
+    }
+}
+
+/// Split the output of a program in lines.
+fn parse_lines(output: &[u8]) -> Vec<String> {
+    if output.is_empty() {
+        return Vec::new();
+    }
+
+    let output = output.strip_suffix(b"\n").unwrap_or(output);
+
+    output
+        .split(|b| *b == b'\n')
+        .map(|line| {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            String::from_utf8_lossy(line).into_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn parse_program_output() {
+    assert!(parse_lines(b"").is_empty());
+    assert_eq!(parse_lines(b"\n"), [""]);
+    assert_eq!(parse_lines(b"a\nb"), ["a", "b"]);
+    assert_eq!(parse_lines(b"a\r\n\nb\n"), ["a", "", "b"]);
+    assert_eq!(parse_lines(b"\xFFa\n"), ["\u{FFFD}a"]);
+}
@@ -0,0 +1,219 @@
+//! This module implements a collector to find regular files with the same
+//! content.
+//!
+//! Files are grouped by their size, and then a hash of the content is computed
+//! for the groups with more than one file. Files with the same hash are
+//! compared byte by byte, so hash collisions are not reported as duplicates.
+//! Empty files, and files larger than `collector.duplicates.max_file_size`,
+//! are ignored.
+//!
+//! Hashes are computed in the thread pool shared with the other collectors.
+//! Files without a result after `collector.timeout` are not considered as
+//! duplicates.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::hash::Hasher;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::exts::read_up_to;
+use super::jobs::Job;
+use crate::config;
+use threadpool::ThreadPool;
+
+/// Default value for `collector.duplicates.max_file_size`.
+const DEFAULT_MAX_FILE_SIZE: u64 = 64 << 20;
+
+/// Size of the buffers to read the files.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Map to associate file names with the group of files with the same content.
+///
+/// Groups are numbered from 1, sorted by the names of their files.
+pub(super) type Duplicates = HashMap<OsString, usize>;
+
+/// Hashes computed in the thread pool. The duplicates are found with
+/// [`PendingDuplicates::wait`].
+pub(super) struct PendingDuplicates {
+    deadline: Option<Instant>,
+    jobs: Vec<(u64, PathBuf, OsString, Job<u64>)>,
+}
+
+/// Launch the jobs to find files with the same content in `entries`.
+pub(super) fn find(
+    entries: &[(PathBuf, OsString, fs::Metadata)],
+    config: &config::Root,
+    threadpool: &ThreadPool,
+) -> PendingDuplicates {
+    let deadline = config
+        .collector
+        .timeout
+        .as_ref()
+        .map(|t| Instant::now() + t.0);
+
+    let settings = match &config.collector.duplicates {
+        Some(d) => d,
+        None => {
+            return PendingDuplicates {
+                deadline,
+                jobs: Vec::new(),
+            }
+        }
+    };
+
+    let max_file_size = settings.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
+
+    // Group files by size.
+    let mut sizes: HashMap<u64, Vec<_>> = HashMap::new();
+    for (path, file_name, metadata) in entries {
+        let size = metadata.len();
+        if metadata.is_file() && size > 0 && size <= max_file_size {
+            sizes.entry(size).or_default().push((path, file_name));
+        }
+    }
+
+    // Compute hashes for files with the same size.
+    let jobs = sizes
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .flat_map(|(size, files)| files.into_iter().map(move |f| (size, f)))
+        .map(|(size, (path, file_name))| {
+            let job_path = path.clone();
+            let job = Job::spawn(threadpool, deadline, move || hash_file(&job_path).ok());
+            (size, path.clone(), file_name.clone(), job)
+        })
+        .collect();
+
+    PendingDuplicates { deadline, jobs }
+}
+
+impl PendingDuplicates {
+    /// Wait for the hashes, and compare the contents of the files with the
+    /// same hash.
+    pub fn wait(self) -> Duplicates {
+        let mut hashes: HashMap<(u64, u64), Vec<(PathBuf, OsString)>> = HashMap::new();
+        for (size, path, file_name, job) in self.jobs {
+            if let Some(hash) = job.get() {
+                hashes
+                    .entry((size, hash))
+                    .or_default()
+                    .push((path, file_name));
+            }
+        }
+
+        let mut groups = Vec::new();
+        for files in hashes.into_values().filter(|files| files.len() > 1) {
+            // Every file is compared with the first file of every group.
+            let mut contents: Vec<Vec<(PathBuf, OsString)>> = Vec::new();
+
+            'files: for file in files {
+                for group in &mut contents {
+                    match same_content(&group[0].0, &file.0, self.deadline) {
+                        Some(true) => {
+                            group.push(file);
+                            continue 'files;
+                        }
+
+                        Some(false) => (),
+
+                        None => continue 'files,
+                    }
+                }
+
+                contents.push(vec![file]);
+            }
+
+            groups.extend(
+                contents
+                    .into_iter()
+                    .filter(|group| group.len() > 1)
+                    .map(|group| group.into_iter().map(|(_, n)| n).collect::<Vec<_>>()),
+            );
+        }
+
+        for group in &mut groups {
+            group.sort_unstable();
+        }
+
+        groups.sort_unstable();
+
+        let mut duplicates = Duplicates::new();
+        for (n, group) in groups.into_iter().enumerate() {
+            for file_name in group {
+                duplicates.insert(file_name, n + 1);
+            }
+        }
+
+        duplicates
+    }
+}
+
+/// Compute a hash of the content of a file.
+fn hash_file(path: &Path) -> io::Result<u64> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buf = vec![0; BUFFER_SIZE];
+
+    loop {
+        match file.read(&mut buf)? {
+            0 => return Ok(hasher.finish()),
+            n => hasher.write(&buf[..n]),
+        }
+    }
+}
+
+/// Compare the contents of two files.
+///
+/// Returns `None` if the files can't be read, or if `deadline` expires before
+/// the comparison is completed.
+fn same_content(a: &Path, b: &Path, deadline: Option<Instant>) -> Option<bool> {
+    let mut file_a = fs::File::open(a).ok()?;
+    let mut file_b = fs::File::open(b).ok()?;
+
+    let mut buf_a = vec![0; BUFFER_SIZE];
+    let mut buf_b = vec![0; BUFFER_SIZE];
+
+    loop {
+        if matches!(deadline, Some(dl) if dl <= Instant::now()) {
+            return None;
+        }
+
+        let len_a = read_up_to(&mut file_a, &mut buf_a).ok()?;
+        let len_b = read_up_to(&mut file_b, &mut buf_b).ok()?;
+
+        if buf_a[..len_a] != buf_b[..len_b] {
+            return Some(false);
+        }
+
+        if len_a == 0 {
+            return Some(true);
+        }
+    }
+}
+
+#[test]
+fn compare_contents() {
+    let root = tempdir::TempDir::new("summer").unwrap();
+    let path = root.path();
+
+    let large = vec![1; BUFFER_SIZE * 2 + 10];
+    let mut large_changed = large.clone();
+    large_changed[BUFFER_SIZE + 5] = 2;
+
+    fs::write(path.join("a"), &large).unwrap();
+    fs::write(path.join("b"), &large).unwrap();
+    fs::write(path.join("c"), &large_changed).unwrap();
+    fs::write(path.join("d"), &large[1..]).unwrap();
+
+    let check = |a, b, deadline| same_content(&path.join(a), &path.join(b), deadline);
+
+    assert_eq!(check("a", "b", None), Some(true));
+    assert_eq!(check("a", "c", None), Some(false));
+    assert_eq!(check("a", "d", None), Some(false));
+    assert_eq!(check("a", "x", None), None);
+    assert_eq!(check("a", "b", Some(Instant::now())), None);
+}
@@ -1,5 +1,9 @@
 //! Shared functions.
 
+use std::io::{self, Read};
+
+use crate::config::Timestamp;
+
 /// Returns the modification time from the file.
 pub fn mtime(metadata: &std::fs::Metadata) -> u64 {
     #[cfg(unix)]
@@ -19,3 +23,48 @@ pub fn mtime(metadata: &std::fs::Metadata) -> u64 {
             .unwrap_or(0)
     }
 }
+
+/// Returns a timestamp from the file, in seconds since the Unix epoch.
+pub fn timestamp(metadata: &std::fs::Metadata, kind: Timestamp) -> Option<i64> {
+    use super::timestamps::unix_seconds;
+
+    match kind {
+        Timestamp::Mtime => Some(mtime(metadata) as i64),
+
+        #[cfg(unix)]
+        Timestamp::Atime => {
+            use std::os::unix::fs::MetadataExt;
+            Some(metadata.atime())
+        }
+
+        #[cfg(unix)]
+        Timestamp::Ctime => {
+            use std::os::unix::fs::MetadataExt;
+            Some(metadata.ctime())
+        }
+
+        #[cfg(not(unix))]
+        Timestamp::Atime => metadata.accessed().ok().map(unix_seconds),
+
+        #[cfg(not(unix))]
+        Timestamp::Ctime => None,
+
+        Timestamp::Btime => metadata.created().ok().map(unix_seconds),
+    }
+}
+
+/// Like `read_exact`, but it accepts a short input.
+pub fn read_up_to<R: Read>(input: &mut R, mut buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while !buf.is_empty() {
+        match input.read(buf)? {
+            0 => break,
+            n => {
+                len += n;
+                buf = &mut buf[n..];
+            }
+        }
+    }
+
+    Ok(len)
+}
@@ -0,0 +1,233 @@
+//! This module formats the metadata fields (see `columns.*.fields`) shown next
+//! to the file names.
+
+use std::collections::HashMap;
+#[cfg(unix)]
+use std::ffi::CStr;
+use std::fs::Metadata;
+use std::time::SystemTime;
+
+use super::render::format_size;
+use super::{timestamps, File};
+use crate::config::{self, Field};
+use crate::display::styles::Style;
+
+/// Format fields for the files in a column.
+///
+/// User and group names are cached.
+pub(super) struct Fields<'a> {
+    time: &'a config::Time,
+    now: SystemTime,
+    users: HashMap<u32, Option<String>>,
+    groups: HashMap<u32, Option<String>>,
+}
+
+/// Returns the name of a user or a group, from its ID.
+#[cfg(unix)]
+fn lookup_name<E>(
+    id: u32,
+    func: unsafe extern "C" fn(
+        u32,
+        *mut E,
+        *mut libc::c_char,
+        libc::size_t,
+        *mut *mut E,
+    ) -> libc::c_int,
+    field: impl FnOnce(&E) -> *mut libc::c_char,
+) -> Option<String> {
+    // SAFETY: `func` is `getpwuid_r` or `getgrgid_r`, and `E` is its entry.
+    //         The name is copied before the buffer is released.
+    unsafe {
+        config::lookup_entry(
+            |e, b, l, r| func(id, e, b, l, r),
+            |e| {
+                let name = field(e);
+                if name.is_null() {
+                    None
+                } else {
+                    Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+                }
+            },
+        )
+        .flatten()
+    }
+}
+
+/// Returns `true` if the field has to be aligned to the right.
+pub(super) fn align_right(field: Field) -> bool {
+    matches!(field, Field::Links | Field::Size)
+}
+
+impl<'a> Fields<'a> {
+    pub(super) fn new(config: &'a config::Root) -> Self {
+        Fields {
+            time: &config.time,
+            now: SystemTime::now(),
+            users: HashMap::new(),
+            groups: HashMap::new(),
+        }
+    }
+
+    /// Format the value of a field for a file.
+    pub(super) fn format(&mut self, field: Field, file: &File) -> Option<String> {
+        let metadata = &file.metadata;
+
+        match field {
+            Field::Permissions => permissions(metadata),
+
+            Field::Links => links(metadata),
+
+            Field::Owner => owner(metadata, &mut self.users),
+
+            Field::Group => group(metadata, &mut self.groups),
+
+            Field::Size => {
+                if metadata.is_file() {
+                    Some(format_size(metadata.len()))
+                } else {
+                    None
+                }
+            }
+
+            Field::ModificationTime => metadata
+                .modified()
+                .ok()
+                .map(|time| timestamps::format_with(self.time, time, self.now)),
+        }
+    }
+
+    /// Style for the value of a field, from `time.styles`.
+    pub(super) fn style(&self, field: Field, file: &File) -> Option<Style> {
+        if !matches!(field, Field::ModificationTime) {
+            return None;
+        }
+
+        let time = file.metadata.modified().ok()?;
+        timestamps::style(self.time, time, self.now)
+    }
+}
+
+#[cfg(unix)]
+fn permissions(metadata: &Metadata) -> Option<String> {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+    let file_type = metadata.file_type();
+    let mode = metadata.mode();
+
+    let kind = if file_type.is_dir() {
+        'd'
+    } else if file_type.is_symlink() {
+        'l'
+    } else if file_type.is_fifo() {
+        'p'
+    } else if file_type.is_socket() {
+        's'
+    } else if file_type.is_block_device() {
+        'b'
+    } else if file_type.is_char_device() {
+        'c'
+    } else {
+        '-'
+    };
+
+    let bit = |mask, c| if mode & mask != 0 { c } else { '-' };
+
+    // Execution bit, combined with setuid, setgid or sticky.
+    let exec = |mask, special, set, unset| match (mode & mask != 0, mode & special != 0) {
+        (true, true) => set,
+        (false, true) => unset,
+        (true, false) => 'x',
+        (false, false) => '-',
+    };
+
+    let chars = [
+        kind,
+        bit(0o400, 'r'),
+        bit(0o200, 'w'),
+        exec(0o100, 0o4000, 's', 'S'),
+        bit(0o040, 'r'),
+        bit(0o020, 'w'),
+        exec(0o010, 0o2000, 's', 'S'),
+        bit(0o004, 'r'),
+        bit(0o002, 'w'),
+        exec(0o001, 0o1000, 't', 'T'),
+    ];
+
+    Some(chars.iter().collect())
+}
+
+#[cfg(unix)]
+fn links(metadata: &Metadata) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.nlink().to_string())
+}
+
+#[cfg(unix)]
+fn owner(metadata: &Metadata, names: &mut HashMap<u32, Option<String>>) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let uid = metadata.uid();
+    let name = names
+        .entry(uid)
+        .or_insert_with(|| lookup_name(uid, libc::getpwuid_r, |e| e.pw_name));
+
+    Some(name.clone().unwrap_or_else(|| uid.to_string()))
+}
+
+#[cfg(unix)]
+fn group(metadata: &Metadata, names: &mut HashMap<u32, Option<String>>) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let gid = metadata.gid();
+    let name = names
+        .entry(gid)
+        .or_insert_with(|| lookup_name(gid, libc::getgrgid_r, |e| e.gr_name));
+
+    Some(name.clone().unwrap_or_else(|| gid.to_string()))
+}
+
+#[cfg(not(unix))]
+fn permissions(_: &Metadata) -> Option<String> {
+    None
+}
+
+#[cfg(not(unix))]
+fn links(_: &Metadata) -> Option<String> {
+    None
+}
+
+#[cfg(not(unix))]
+fn owner(_: &Metadata, _: &mut HashMap<u32, Option<String>>) -> Option<String> {
+    None
+}
+
+#[cfg(not(unix))]
+fn group(_: &Metadata, _: &mut HashMap<u32, Option<String>>) -> Option<String> {
+    None
+}
+
+#[cfg(unix)]
+#[test]
+fn format_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempdir::TempDir::new("summer").unwrap();
+    let path = dir.path().join("file");
+    std::fs::write(&path, "").unwrap();
+
+    for (mode, expected) in [
+        (0o644, "-rw-r--r--"),
+        (0o755, "-rwxr-xr-x"),
+        (0o4755, "-rwsr-xr-x"),
+        (0o2644, "-rw-r-Sr--"),
+        (0o1777, "-rwxrwxrwt"),
+        (0o1776, "-rwxrwxrwT"),
+    ] {
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        let metadata = std::fs::metadata(&path).unwrap();
+        assert_eq!(permissions(&metadata).as_deref(), Some(expected));
+    }
+
+    let metadata = std::fs::metadata(dir.path()).unwrap();
+    assert_eq!(&permissions(&metadata).unwrap()[..1], "d");
+}
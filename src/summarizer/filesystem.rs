@@ -0,0 +1,197 @@
+//! This module provides functions to get information about the filesystem that
+//! contains a path:
+//!
+//! * [`space`] returns the total, used and free space, from `statvfs(3)`.
+//! * [`mount`] returns the mount point and the filesystem type. In Linux, it
+//!   is read from `/proc/self/mountinfo`. It is not available in other
+//!   systems.
+
+use std::path::{Path, PathBuf};
+
+/// Space in a filesystem, in bytes.
+#[derive(Copy, Clone)]
+#[cfg_attr(test, derive(Debug))]
+pub(super) struct Space {
+    pub total: u64,
+    pub used: u64,
+    pub free: u64,
+}
+
+/// Mount point and type of a filesystem.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub(super) struct Mount {
+    pub point: PathBuf,
+    pub fs_type: String,
+}
+
+impl Space {
+    /// Percentage of used space, like the `Use%` column of `df(1)`.
+    ///
+    /// The value is relative to the space available to non-privileged users.
+    pub fn usage(&self) -> u64 {
+        match self.used + self.free {
+            0 => 0,
+            size => (self.used as f64 * 100.0 / size as f64).ceil() as u64,
+        }
+    }
+}
+
+/// Read the total, used and free space in the filesystem that contains `path`.
+#[cfg(unix)]
+pub(super) fn space(path: &Path) -> Option<Space> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+
+    // SAFETY: `statvfs` only writes to the struct, and `path` is a valid C
+    //         string.
+    let stat = unsafe {
+        let mut stat: libc::statvfs = std::mem::zeroed();
+        if libc::statvfs(path.as_ptr(), &mut stat) != 0 {
+            return None;
+        }
+
+        stat
+    };
+
+    #[allow(clippy::useless_conversion)]
+    let (blocks, bfree, bavail, frsize) = (
+        u64::from(stat.f_blocks),
+        u64::from(stat.f_bfree),
+        u64::from(stat.f_bavail),
+        u64::from(stat.f_frsize),
+    );
+
+    Some(Space {
+        total: blocks * frsize,
+        used: blocks.saturating_sub(bfree) * frsize,
+        free: bavail * frsize,
+    })
+}
+
+#[cfg(not(unix))]
+pub(super) fn space(_: &Path) -> Option<Space> {
+    None
+}
+
+/// Find the mount point that contains `path`.
+///
+/// `path` is expected to be canonicalized.
+#[cfg(target_os = "linux")]
+pub(super) fn mount(path: &Path) -> Option<Mount> {
+    let mountinfo = std::fs::read("/proc/self/mountinfo").ok()?;
+    find_mount(&mountinfo, path)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) fn mount(_: &Path) -> Option<Mount> {
+    None
+}
+
+/// Find the longest mount point, in the contents of a `mountinfo` file, that
+/// contains `path`.
+///
+/// The format of the file is described in the `proc(5)` manual page.
+#[cfg(target_os = "linux")]
+fn find_mount(mountinfo: &[u8], path: &Path) -> Option<Mount> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut found: Option<Mount> = None;
+
+    for line in mountinfo.split(|b| *b == b'\n') {
+        let mut fields = line.split(|b| *b == b' ');
+
+        let point = match fields.nth(4) {
+            Some(p) => PathBuf::from(OsStr::from_bytes(&unescape(p))),
+            None => continue,
+        };
+
+        // The filesystem type is after the separator of the optional fields.
+        let fs_type = match fields.skip_while(|f| *f != b"-").nth(1) {
+            Some(t) => String::from_utf8_lossy(&unescape(t)).into_owned(),
+            None => continue,
+        };
+
+        let is_longer = match &found {
+            Some(f) => point.as_os_str().len() >= f.point.as_os_str().len(),
+            None => true,
+        };
+
+        if is_longer && path.starts_with(&point) {
+            found = Some(Mount { point, fs_type });
+        }
+    }
+
+    found
+}
+
+/// Replace octal escapes (like `\040` for spaces) in a field of the
+/// `mountinfo` file.
+#[cfg(target_os = "linux")]
+fn unescape(field: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(field.len());
+    let mut bytes = field;
+
+    while let Some((first, tail)) = bytes.split_first() {
+        match tail {
+            [a @ b'0'..=b'3', b @ b'0'..=b'7', c @ b'0'..=b'7', rest @ ..] if *first == b'\\' => {
+                output.push(((a - b'0') << 6) | ((b - b'0') << 3) | (c - b'0'));
+                bytes = rest;
+            }
+
+            _ => {
+                output.push(*first);
+                bytes = tail;
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn parse_mountinfo() {
+    let mountinfo = b"\
+        22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw\n\
+        23 22 0:22 / /proc rw,relatime - proc proc rw\n\
+        26 22 0:24 / /dev/shm rw,relatime shared:4 master:1 - tmpfs tmpfs rw\n\
+        27 22 0:25 / /mnt/a\\040b rw - vfat /dev/sdb1 rw\n";
+
+    macro_rules! check {
+        ($path:expr, $point:expr, $fs_type:expr) => {
+            assert_eq!(
+                find_mount(mountinfo, Path::new($path)),
+                Some(Mount {
+                    point: PathBuf::from($point),
+                    fs_type: $fs_type.to_owned(),
+                })
+            );
+        };
+    }
+
+    check!("/", "/", "ext4");
+    check!("/home/user", "/", "ext4");
+    check!("/dev/shm/x", "/dev/shm", "tmpfs");
+    check!("/dev/shmx", "/", "ext4");
+    check!("/mnt/a b/c", "/mnt/a b", "vfat");
+
+    assert_eq!(find_mount(b"", Path::new("/")), None);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn read_tmpfs_space() {
+    let shm = Path::new("/dev/shm");
+    if !shm.is_dir() {
+        return;
+    }
+
+    let space = space(shm).unwrap();
+    assert!(space.total >= space.used);
+    assert!(space.usage() <= 100);
+
+    assert_eq!(mount(shm).map(|m| m.fs_type).as_deref(), Some("tmpfs"));
+}
@@ -0,0 +1,131 @@
+//! This module computes the colors for the `colors.heatmap` setting.
+//!
+//! Every file gets a position in the range `0..=1`, which is used to
+//! interpolate a color in the gradient:
+//!
+//! * With `by: size`, the position is the percentile of the file size among all
+//!   regular files in the columns.
+//! * With `by: age`, the position is the age of the file relative to
+//!   `max_age`. If `max_age` is not set, it is the percentile of the
+//!   modification time among all files in the columns, where `0` is the newest
+//!   file.
+
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+use ansi_term::Color;
+
+use super::{Analysis, File};
+use crate::config::{self, HeatmapKey};
+use crate::display::styles::Style;
+
+pub(super) struct Heatmap<'a> {
+    config: &'a config::Heatmap,
+    now: SystemTime,
+
+    /// Sorted values of all files in the columns, to compute percentiles.
+    values: Vec<u64>,
+}
+
+impl<'a> Heatmap<'a> {
+    pub(super) fn new(analysis: &Analysis, config: &'a config::Heatmap) -> Self {
+        let now = SystemTime::now();
+
+        let mut values = Vec::new();
+        if matches!(config.by, HeatmapKey::Size) || config.max_age.is_none() {
+            // Files in non-exclusive columns can appear in multiple groups.
+            let mut seen = HashSet::new();
+
+            values.extend(
+                analysis
+                    .groups
+                    .iter()
+                    .flat_map(|g| g.files.iter())
+                    .filter(|file| seen.insert(Rc::as_ptr(file)))
+                    .filter_map(|file| value(config.by, now, file)),
+            );
+
+            values.sort_unstable();
+        }
+
+        Heatmap {
+            config,
+            now,
+            values,
+        }
+    }
+
+    /// Returns the style for a file, or `None` if the file can't be placed
+    /// in the heatmap.
+    pub(super) fn style(&self, file: &File) -> Option<Style> {
+        let value = value(self.config.by, self.now, file)?;
+
+        let position = match (self.config.by, &self.config.max_age) {
+            (HeatmapKey::Age, Some(max_age)) => {
+                let max_age = max_age.0.as_secs().max(1);
+                value.min(max_age) as f64 / max_age as f64
+            }
+
+            _ => {
+                let rank = self.values.partition_point(|v| *v < value);
+                rank as f64 / (self.values.len().max(2) - 1) as f64
+            }
+        };
+
+        let (r, g, b) = interpolate(&self.config.colors.colors, position);
+        Some(Style::new().fg(Color::RGB(r, g, b)))
+    }
+}
+
+/// Value of the file used to place it in the heatmap.
+fn value(key: HeatmapKey, now: SystemTime, file: &File) -> Option<u64> {
+    match key {
+        HeatmapKey::Size => {
+            if file.metadata.is_file() {
+                Some(file.metadata.len())
+            } else {
+                None
+            }
+        }
+
+        HeatmapKey::Age => {
+            let mtime = file.metadata.modified().ok()?;
+            let age = now.duration_since(mtime).unwrap_or(Duration::ZERO);
+            Some(age.as_secs())
+        }
+    }
+}
+
+/// Compute the color at `position` in a gradient.
+fn interpolate(colors: &[(u8, u8, u8)], position: f64) -> (u8, u8, u8) {
+    let last = colors.len() - 1;
+    let position = position.clamp(0.0, 1.0) * last as f64;
+
+    let index = (position.floor() as usize).min(last.saturating_sub(1));
+    let t = position - index as f64;
+
+    let (a, b) = match (colors.get(index), colors.get(index + 1)) {
+        (Some(a), Some(b)) => (a, b),
+        (Some(a), None) => return *a,
+        _ => return (0, 0, 0),
+    };
+
+    let mix = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * t).round() as u8;
+    (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+#[test]
+fn interpolate_gradients() {
+    let colors = [(0, 0, 0), (200, 100, 0), (200, 200, 200)];
+
+    assert_eq!(interpolate(&colors, 0.0), (0, 0, 0));
+    assert_eq!(interpolate(&colors, 0.25), (100, 50, 0));
+    assert_eq!(interpolate(&colors, 0.5), (200, 100, 0));
+    assert_eq!(interpolate(&colors, 0.75), (200, 150, 100));
+    assert_eq!(interpolate(&colors, 1.0), (200, 200, 200));
+    assert_eq!(interpolate(&colors, 7.0), (200, 200, 200));
+    assert_eq!(interpolate(&colors, -1.0), (0, 0, 0));
+
+    assert_eq!(interpolate(&colors[..1], 0.5), (0, 0, 0));
+}
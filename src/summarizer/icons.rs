@@ -0,0 +1,67 @@
+//! This module provides the built-in icon set (see `colors.icons`).
+//!
+//! Icons are selected by the file type, and then by the top-level MIME type
+//! of the extension.
+
+use std::path::Path;
+
+use super::File;
+use crate::config::MimeType;
+
+/// Icon for regular files with no other icon.
+const DEFAULT_FILE_ICON: &str = "📄";
+
+/// Returns the built-in icon for a file.
+pub(super) fn builtin(file: &File) -> &'static str {
+    let file_type = file.metadata.file_type();
+
+    if file_type.is_dir() {
+        return "📁";
+    }
+
+    if file_type.is_symlink() {
+        return "🔗";
+    }
+
+    #[cfg(unix)]
+    {
+        use crate::config::Mode;
+        use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+        if file_type.is_block_device() {
+            return "💾";
+        }
+
+        if file_type.is_char_device() {
+            return "📟";
+        }
+
+        if file_type.is_fifo() {
+            return "🚰";
+        }
+
+        if file_type.is_socket() {
+            return "🔌";
+        }
+
+        if Mode::EXECUTABLE.is_match(file.metadata.mode()) {
+            return "⚡";
+        }
+    }
+
+    let mime_type = Path::new(&file.file_name)
+        .extension()
+        .and_then(MimeType::from_extension);
+
+    match mime_type {
+        Some(MimeType::APPLICATION) => "📦",
+        Some(MimeType::AUDIO) => "🎵",
+        Some(MimeType::FONT) => "🔤",
+        Some(MimeType::IMAGE) => "🎨",
+        Some(MimeType::MESSAGE) => "📧",
+        Some(MimeType::MODEL) => "🧊",
+        Some(MimeType::TEXT) => "📝",
+        Some(MimeType::VIDEO) => "🎬",
+        _ => DEFAULT_FILE_ICON,
+    }
+}
@@ -0,0 +1,255 @@
+//! This module provides the [`read_size`] function, which reads the headers of
+//! an image file to get its dimensions.
+//!
+//! Supported formats are PNG, JPEG, GIF and WebP. Only a few bytes at the
+//! beginning of the file are read, except for JPEG files, where the parser has
+//! to skip the segments before the frame header.
+//!
+//! The [`ImageReader`] collector reads the headers in a thread pool. Sizes
+//! that are not ready when the deadline in `collector.timeout` is reached are
+//! discarded.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Instant;
+
+use super::exts::read_up_to;
+use super::jobs::Job;
+use crate::config::{self, MimeType};
+use threadpool::ThreadPool;
+
+/// Maximum number of JPEG segments to read before the frame header.
+const MAX_JPEG_SEGMENTS: usize = 64;
+
+/// Collector to read the dimensions of image files.
+pub(super) struct ImageReader {
+    deadline: Option<Instant>,
+    threadpool: ThreadPool,
+}
+
+/// Dimensions of an image, computed in a background thread.
+pub(super) type ImageSizeJob = Job<ImageSize>;
+
+/// Dimensions of an image, in pixels.
+#[derive(Copy, Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct ImageSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Read the dimensions of the image in `path`.
+///
+/// Returns `None` if the file can't be read, or if its format is not
+/// supported.
+pub fn read_size(path: &Path) -> Option<ImageSize> {
+    let mut file = BufReader::new(File::open(path).ok()?);
+    parse_size(&mut file).ok().flatten()
+}
+
+impl ImageReader {
+    /// Returns a reader if the dimensions are needed, either because
+    /// `collector.image_size` is enabled, or because a column shows them.
+    pub fn new(config: &config::Root, threadpool: &ThreadPool) -> Option<ImageReader> {
+        if !config.collector.image_size && !config.columns.iter().any(|c| c.image_size) {
+            return None;
+        }
+
+        let deadline = config
+            .collector
+            .timeout
+            .as_ref()
+            .map(|t| Instant::now() + t.0);
+
+        Some(ImageReader {
+            deadline,
+            threadpool: threadpool.clone(),
+        })
+    }
+
+    /// Read the dimensions of the image in a background thread. Only regular
+    /// files with an image extension are read.
+    pub fn read(&self, path: &Path, metadata: &fs::Metadata) -> Option<ImageSizeJob> {
+        if !metadata.is_file()
+            || path.extension().and_then(MimeType::from_extension) != Some(MimeType::IMAGE)
+        {
+            return None;
+        }
+
+        let path = path.to_owned();
+        Some(Job::spawn(&self.threadpool, self.deadline, move || {
+            read_size(&path)
+        }))
+    }
+}
+
+impl ImageSize {
+    fn new(width: u32, height: u32) -> ImageSize {
+        ImageSize { width, height }
+    }
+}
+
+fn parse_size<R: Read + Seek>(input: &mut R) -> io::Result<Option<ImageSize>> {
+    let mut header = [0; 30];
+    let len = read_up_to(input, &mut header)?;
+    let header = &header[..len];
+
+    macro_rules! u16_le {
+        ($offset:expr) => {
+            u32::from(u16::from_le_bytes([header[$offset], header[$offset + 1]]))
+        };
+    }
+
+    macro_rules! u24_le {
+        ($offset:expr) => {
+            u32::from_le_bytes([header[$offset], header[$offset + 1], header[$offset + 2], 0])
+        };
+    }
+
+    macro_rules! u32_be {
+        ($offset:expr) => {
+            u32::from_be_bytes([
+                header[$offset],
+                header[$offset + 1],
+                header[$offset + 2],
+                header[$offset + 3],
+            ])
+        };
+    }
+
+    let size = match header {
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n', _, _, _, _, b'I', b'H', b'D', b'R', ..]
+            if len >= 24 =>
+        {
+            ImageSize::new(u32_be!(16), u32_be!(20))
+        }
+
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] if len >= 10 => {
+            ImageSize::new(u16_le!(6), u16_le!(8))
+        }
+
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', b'V', b'P', b'8', kind, ..] => {
+            match kind {
+                b' ' if len >= 30 => ImageSize::new(u16_le!(26) & 0x3FFF, u16_le!(28) & 0x3FFF),
+
+                b'L' if len >= 25 && header[20] == 0x2F => {
+                    let bits = u32::from_le_bytes([header[21], header[22], header[23], header[24]]);
+                    ImageSize::new((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1)
+                }
+
+                b'X' if len >= 30 => ImageSize::new(u24_le!(24) + 1, u24_le!(27) + 1),
+
+                _ => return Ok(None),
+            }
+        }
+
+        [0xFF, 0xD8, ..] => {
+            input.seek(SeekFrom::Start(2))?;
+            return parse_jpeg(input);
+        }
+
+        _ => return Ok(None),
+    };
+
+    Ok(Some(size))
+}
+
+/// Find the frame header (`SOFn`) in a JPEG file.
+fn parse_jpeg<R: Read + Seek>(input: &mut R) -> io::Result<Option<ImageSize>> {
+    let mut byte = [0];
+
+    for _ in 0..MAX_JPEG_SEGMENTS {
+        // Find the marker, skipping fill bytes.
+        input.read_exact(&mut byte)?;
+        if byte[0] != 0xFF {
+            return Ok(None);
+        }
+
+        while byte[0] == 0xFF {
+            input.read_exact(&mut byte)?;
+        }
+
+        let marker = byte[0];
+
+        // Markers without payload.
+        if marker == 0x01 || (0xD0..=0xD8).contains(&marker) {
+            continue;
+        }
+
+        let mut length = [0; 2];
+        input.read_exact(&mut length)?;
+        let length = u16::from_be_bytes(length);
+
+        match marker {
+            0xC0..=0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF => {
+                let mut frame = [0; 5];
+                input.read_exact(&mut frame)?;
+
+                let height = u16::from_be_bytes([frame[1], frame[2]]);
+                let width = u16::from_be_bytes([frame[3], frame[4]]);
+                return Ok(Some(ImageSize::new(width.into(), height.into())));
+            }
+
+            0xD9 | 0xDA => return Ok(None),
+
+            _ => {
+                input.seek(SeekFrom::Current(i64::from(length) - 2))?;
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+#[test]
+fn parse_image_headers() {
+    use std::io::Cursor;
+
+    macro_rules! check {
+        ($data:expr, $size:expr) => {
+            let mut data = Vec::new();
+            data.extend_from_slice(&$data[..]);
+            let size = parse_size(&mut Cursor::new(data)).unwrap();
+            assert_eq!(size, $size.map(|(w, h)| ImageSize::new(w, h)));
+        };
+    }
+
+    // PNG
+    check!(
+        b"\x89PNG\r\n\x1a\n\0\0\0\x0DIHDR\0\0\x07\x80\0\0\x04\x38\x08\x02",
+        Some((1920, 1080))
+    );
+
+    // GIF
+    check!(b"GIF89a\x40\x01\xC8\x00\xF7\x00", Some((320, 200)));
+    check!(b"GIF87a\x01\x00\x01\x00", Some((1, 1)));
+
+    // WebP
+    check!(
+        b"RIFF\0\0\0\0WEBPVP8 \0\0\0\0\x10\x02\0\x9d\x01\x2a\x80\x02\xe0\x01",
+        Some((640, 480))
+    );
+
+    check!(
+        b"RIFF\0\0\0\0WEBPVP8L\0\0\0\0\x2f\x7f\xc0\x3b\x00",
+        Some((128, 240))
+    );
+
+    check!(
+        b"RIFF\0\0\0\0WEBPVP8X\0\0\0\0\0\0\0\0\x7f\x07\0\x37\x04\0",
+        Some((1920, 1080))
+    );
+
+    // JPEG, with an APP0 segment before the frame header.
+    check!(
+        b"\xFF\xD8\xFF\xE0\x00\x04\x00\x00\xFF\xC0\x00\x11\x08\x02\x58\x03\x20\x03",
+        Some((800, 600))
+    );
+
+    check!(b"\xFF\xD8\xFF\xDA\x00\x04\x00\x00", None);
+
+    // Invalid data.
+    check!(b"\x89PNG", None);
+    check!(b"", None);
+}
@@ -13,15 +13,40 @@
 //! %P      path
 //! %p      path, where '$HOME' is replaced with '~'.
 //! %S      disk usage
+//! %T      markers (like TODO) in text files
+//! %M      modification time, formatted with the `time` settings
 //! %+      added lines (git)
 //! %-      deleted lines (git)
 //! %C{…}   color
 //! %V{…}   variable
+//! %F{…}   filesystem
+//! ```
+//!
+//! # Filesystem
+//!
+//! `%F{…}` prints information about the filesystem that contains the path. The
+//! field can be any of the following:
+//!
+//! ```notrust
+//! total   total space
+//! used    used space
+//! free    space available to non-privileged users
+//! usage   percentage of used space
+//! mount   mount point
+//! type    filesystem type
+//! bar     bar to represent the used space. The width (10 by default) can be
+//!         set with `bar:N`, up to 200.
 //! ```
 
 use ansi_term::Style;
 use std::mem;
 
+/// Default width for `%F{bar}`.
+const DEFAULT_BAR_WIDTH: usize = 10;
+
+/// Maximum width for `%F{bar:N}`.
+const MAX_BAR_WIDTH: usize = 200;
+
 /// Tokes extracted from a formatting string.
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub(super) enum Token<'a> {
@@ -32,8 +57,24 @@ pub(super) enum Token<'a> {
     Path,
     PathHome,
     DiskUsage,
+    Markers,
+    ModificationTime,
     AddedLines,
     DeletedLines,
+    Filesystem(FsField),
+}
+
+/// Fields for the `%F{…}` specifier.
+#[derive(Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub(super) enum FsField {
+    Total,
+    Used,
+    Free,
+    Usage,
+    Mount,
+    Type,
+    Bar(usize),
 }
 
 /// Parse a formatting string, and returns an iterator over the tokens in it.
@@ -55,10 +96,13 @@ impl<'a> Parser<'a> {
             'P' => (Token::Path, 1),
             'p' => (Token::PathHome, 1),
             'S' => (Token::DiskUsage, 1),
+            'T' => (Token::Markers, 1),
+            'M' => (Token::ModificationTime, 1),
             '+' => (Token::AddedLines, 1),
             '-' => (Token::DeletedLines, 1),
             'C' => Self::parse_color(format)?,
             'V' => Self::parse_variable(format)?,
+            'F' => Self::parse_filesystem(format)?,
             '%' => (Token::Text("%"), 1),
             _ => return None,
         };
@@ -85,6 +129,30 @@ impl<'a> Parser<'a> {
         Some((Token::Variable(var), end + 1))
     }
 
+    /// Parse `%F{..}` specifiers.
+    fn parse_filesystem(format: &str) -> Option<(Token, usize)> {
+        let end = memchr::memchr(b'}', format.as_bytes())?;
+        let field = match format[..end].strip_prefix("F{")?.trim() {
+            "total" => FsField::Total,
+            "used" => FsField::Used,
+            "free" => FsField::Free,
+            "usage" => FsField::Usage,
+            "mount" => FsField::Mount,
+            "type" => FsField::Type,
+            "bar" => FsField::Bar(DEFAULT_BAR_WIDTH),
+            field => {
+                let width = field.strip_prefix("bar:")?.trim().parse().ok()?;
+                if width > MAX_BAR_WIDTH {
+                    return None;
+                }
+
+                FsField::Bar(width)
+            }
+        };
+
+        Some((Token::Filesystem(field), end + 1))
+    }
+
     /// Consume all pending input in the formatting string.
     fn consume_pending(&mut self) -> Option<Token<'a>> {
         if self.0.is_empty() {
@@ -140,7 +208,7 @@ fn parse_format_string() {
 
     // A string with all specifiers.
     parse!(
-        "%C{blue bold} %P %p : %S%+%-%C{reset}%C{red}%V{dirs} %%dirs",
+        "%C{blue bold} %P %p : %S%T%M%+%-%C{reset}%C{red}%V{dirs} %%dirs",
         Style(AtStyle::new().fg(Colour::Blue).bold()),
         Text(" "),
         Path,
@@ -148,6 +216,8 @@ fn parse_format_string() {
         PathHome,
         Text(" : "),
         DiskUsage,
+        Markers,
+        ModificationTime,
         AddedLines,
         DeletedLines,
         StyleReset,
@@ -158,6 +228,21 @@ fn parse_format_string() {
         Text("dirs")
     );
 
+    // Filesystem fields.
+    parse!(
+        "%F{total}%F{used}%F{free}%F{usage}%F{mount}%F{type}%F{bar}%F{bar:5}%F{x}%F{bar:201}",
+        Filesystem(FsField::Total),
+        Filesystem(FsField::Used),
+        Filesystem(FsField::Free),
+        Filesystem(FsField::Usage),
+        Filesystem(FsField::Mount),
+        Filesystem(FsField::Type),
+        Filesystem(FsField::Bar(DEFAULT_BAR_WIDTH)),
+        Filesystem(FsField::Bar(5)),
+        Text("%F{x}"),
+        Text("%F{bar:201}")
+    );
+
     // % at the end.
     parse!("aaa%", Text("aaa%"));
     parse!("%P%", Token::Path, Token::Text("%"));
@@ -0,0 +1,57 @@
+//! This module implements the [`Job`] type, used by the collectors to compute
+//! values in a thread pool.
+//!
+//! Results after the deadline are discarded.
+
+use std::cell::RefCell;
+use std::sync::mpsc;
+use std::time::Instant;
+
+use threadpool::ThreadPool;
+
+/// Value computed by a background thread.
+#[cfg_attr(test, derive(Debug))]
+pub(super) struct Job<T>(RefCell<JobState<T>>);
+
+#[cfg_attr(test, derive(Debug))]
+enum JobState<T> {
+    Working(Option<Instant>, mpsc::Receiver<Option<T>>),
+    Done(Option<T>),
+}
+
+impl<T: Copy + Send + 'static> Job<T> {
+    /// Execute `task` in the thread pool.
+    pub fn spawn<F>(pool: &ThreadPool, deadline: Option<Instant>, task: F) -> Job<T>
+    where
+        F: FnOnce() -> Option<T> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+
+        pool.execute(move || {
+            let _ = tx.send(task());
+        });
+
+        Job(RefCell::new(JobState::Working(deadline, rx)))
+    }
+
+    /// Returns the value computed by a background thread.
+    ///
+    /// If the value is still unavailable, it will wait until `deadline`.
+    pub fn get(&self) -> Option<T> {
+        let mut inner = self.0.borrow_mut();
+
+        let (deadline, rx) = match &mut *inner {
+            JobState::Done(n) => return *n,
+            JobState::Working(d, r) => (d, r),
+        };
+
+        let timeout = deadline.map(|dl| dl.saturating_duration_since(Instant::now()));
+        let res = match timeout {
+            Some(t) => rx.recv_timeout(t).ok().flatten(),
+            None => rx.recv().ok().flatten(),
+        };
+
+        *inner = JobState::Done(res);
+        res
+    }
+}
@@ -0,0 +1,197 @@
+//! This module implements a collector to count markers (like `TODO` or
+//! `FIXME`) in text files.
+//!
+//! Markers are found with the regular expression in `collector.markers.regex`.
+//! For directories, the collector returns the sum of all files in the tree.
+//! Hidden files and directories are ignored.
+//!
+//! A file is considered binary, and then ignored, if it contains a NUL byte.
+//! Files larger than `collector.markers.max_file_size` are also ignored.
+//!
+//! Each file or directory is scanned in its own job in the thread pool. Scans
+//! stop when the deadline is reached, and the partial count is discarded.
+//! Directories deeper than [`MAX_DEPTH`] are not scanned.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::jobs::Job;
+use crate::config;
+use threadpool::ThreadPool;
+
+/// Default pattern to find markers.
+const DEFAULT_REGEX: &str = "TODO|FIXME|XXX";
+
+/// Default value for `collector.markers.max_file_size`.
+const DEFAULT_MAX_FILE_SIZE: u64 = 1 << 20;
+
+/// Maximum depth of the directories scanned below an entry.
+const MAX_DEPTH: usize = 32;
+
+/// Collector to count markers in a path.
+pub(super) struct MarkersReader {
+    deadline: Option<Instant>,
+    threadpool: ThreadPool,
+    scanner: Arc<Scanner>,
+}
+
+/// Number of markers found in a path.
+pub(super) type MarkersJob = Job<u64>;
+
+struct Scanner {
+    regex: regex::Regex,
+    max_file_size: u64,
+}
+
+impl MarkersReader {
+    pub fn new(config: &config::Root, threadpool: &ThreadPool) -> Option<MarkersReader> {
+        let markers = config.collector.markers.as_ref()?;
+
+        let deadline = config
+            .collector
+            .timeout
+            .as_ref()
+            .map(|t| Instant::now() + t.0);
+
+        let regex = match &markers.regex {
+            Some(re) => re.0.clone(),
+            None => regex::Regex::new(DEFAULT_REGEX).unwrap(),
+        };
+
+        let scanner = Scanner {
+            regex,
+            max_file_size: markers.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE),
+        };
+
+        Some(MarkersReader {
+            deadline,
+            threadpool: threadpool.clone(),
+            scanner: Arc::new(scanner),
+        })
+    }
+
+    /// Count markers in the path in a background thread.
+    pub fn count(&self, path: &Path, metadata: &fs::Metadata) -> Option<MarkersJob> {
+        if !metadata.is_file() && !metadata.is_dir() {
+            return None;
+        }
+
+        if is_hidden(path) {
+            return None;
+        }
+
+        let path = path.to_owned();
+        let scanner = Arc::clone(&self.scanner);
+        let is_dir = metadata.is_dir();
+        let deadline = self.deadline;
+
+        let job = Job::spawn(&self.threadpool, deadline, move || {
+            if is_dir {
+                scanner.scan_dir(&path, deadline, 0)
+            } else {
+                scanner.scan_file(&path)
+            }
+        });
+
+        Some(job)
+    }
+}
+
+impl Scanner {
+    /// Returns the number of markers in a file, or `None` if the file is
+    /// ignored.
+    fn scan_file(&self, path: &Path) -> Option<u64> {
+        let metadata = path.metadata().ok()?;
+        if metadata.len() > self.max_file_size {
+            return None;
+        }
+
+        let data = fs::read(path).ok()?;
+        if memchr::memchr(0, &data).is_some() {
+            return None;
+        }
+
+        let text = String::from_utf8_lossy(&data);
+        Some(self.regex.find_iter(&text).count() as u64)
+    }
+
+    /// Returns the sum of the markers of all files in a directory tree.
+    ///
+    /// Returns `None` if `deadline` expires before the scan is completed.
+    fn scan_dir(&self, path: &Path, deadline: Option<Instant>, depth: usize) -> Option<u64> {
+        let dir = match fs::read_dir(path) {
+            Ok(d) => d,
+            Err(_) => return Some(0),
+        };
+
+        let mut sum = 0;
+
+        for entry in dir.filter_map(|e| e.ok()) {
+            if matches!(deadline, Some(dl) if dl <= Instant::now()) {
+                return None;
+            }
+
+            if is_hidden(Path::new(&entry.file_name())) {
+                continue;
+            }
+
+            let metadata = match entry.metadata() {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+
+            if metadata.is_dir() {
+                if depth < MAX_DEPTH {
+                    sum += self.scan_dir(&entry.path(), deadline, depth + 1)?;
+                }
+            } else if metadata.is_file() {
+                sum += self.scan_file(&entry.path()).unwrap_or(0);
+            }
+        }
+
+        Some(sum)
+    }
+}
+
+/// Returns `true` if the file name starts with a dot.
+fn is_hidden(path: &Path) -> bool {
+    matches!(path.file_name(), Some(name) if name.to_string_lossy().starts_with('.'))
+}
+
+#[test]
+fn count_markers() {
+    let root = tempdir::TempDir::new("summer").unwrap();
+    let path = root.path();
+
+    fs::create_dir_all(path.join("a/b")).unwrap();
+    fs::create_dir_all(path.join(".hidden")).unwrap();
+
+    fs::write(path.join("a/x"), "TODO\nFIXME: XXX").unwrap();
+    fs::write(path.join("a/b/y"), "// TODO").unwrap();
+    fs::write(path.join("a/b/bin"), "TODO\0").unwrap();
+    fs::write(path.join("a/b/big"), "TODO ".repeat(10)).unwrap();
+    fs::write(path.join(".hidden/z"), "TODO").unwrap();
+
+    let scanner = Scanner {
+        regex: regex::Regex::new(DEFAULT_REGEX).unwrap(),
+        max_file_size: 20,
+    };
+
+    assert_eq!(scanner.scan_file(&path.join("a/x")), Some(3));
+    assert_eq!(scanner.scan_file(&path.join("a/b/bin")), None);
+    assert_eq!(scanner.scan_file(&path.join("a/b/big")), None);
+    assert_eq!(scanner.scan_dir(&path.join("a"), None, 0), Some(4));
+    assert_eq!(scanner.scan_dir(path, None, 0), Some(4));
+
+    // Directories below the maximum depth are ignored.
+    assert_eq!(scanner.scan_dir(path, None, MAX_DEPTH), Some(0));
+    assert_eq!(scanner.scan_dir(path, None, MAX_DEPTH - 1), Some(3));
+
+    assert!(is_hidden(&path.join(".hidden")));
+    assert!(!is_hidden(&path.join("a")));
+
+    // The scan is stopped after the deadline.
+    assert_eq!(scanner.scan_dir(path, Some(Instant::now()), 0), None);
+}
@@ -5,22 +5,23 @@ use std::fs;
 use std::path::Path;
 use std::time::SystemTime;
 
-use super::exts::mtime;
-use super::gitdiff::Change;
-use super::treereader::TreeInfoJob;
-use crate::config::{Changes, FileType, Matcher, MimeType};
+use super::exts::{self, mtime};
+use super::timestamps::unix_seconds;
+use super::File;
+use crate::config::{Changes, ChangesRange, FileType, Matcher, MimeType, TimeLimit, Timestamp};
 
 /// Returns `true` if the file matches any of the items in `matchers`.
 ///
 /// If `include_hidden` is `false`, the file is ignored if it starts with a `.`.
 pub(super) fn is_match<'a>(
-    path: &Path,
-    metadata: &fs::Metadata,
-    tree_info: Option<&TreeInfoJob>,
-    change: Option<&Change>,
+    file: &File,
     include_hidden: bool,
     matchers: impl IntoIterator<Item = &'a Matcher>,
 ) -> bool {
+    let path = Path::new(&file.file_name);
+    let metadata = &file.metadata;
+    let tree_info = file.tree_info.as_ref();
+
     let mut cached_mime_type = None;
 
     if !include_hidden {
@@ -38,17 +39,14 @@ pub(super) fn is_match<'a>(
             }
 
             Matcher::All(matchers) => {
-                if matchers
-                    .iter()
-                    .all(|m| is_match(path, metadata, tree_info, change, include_hidden, [m]))
-                {
+                if matchers.iter().all(|m| is_match(file, include_hidden, [m])) {
                     return true;
                 }
             }
 
             Matcher::Changes(changes) => match changes {
                 Changes::Git => {
-                    if change.is_some() {
+                    if file.git_changes.is_some() {
                         return true;
                     }
                 }
@@ -68,14 +66,57 @@ pub(super) fn is_match<'a>(
                         return true;
                     }
                 }
+
+                Changes::Range(range) => {
+                    if is_in_range(file, range) {
+                        return true;
+                    }
+                }
             },
 
+            Matcher::Command(command) => {
+                if let Some(value) = file.command_values.get(&command.name) {
+                    match &command.equals {
+                        Some(expected) if expected != value => (),
+                        _ => return true,
+                    }
+                }
+            }
+
+            Matcher::Duplicate => {
+                if file.duplicate.is_some() {
+                    return true;
+                }
+            }
+
             Matcher::Glob(glob) => {
                 if glob.globs.is_match(path) {
                     return true;
                 }
             }
 
+            #[cfg(unix)]
+            Matcher::Group(group) => {
+                use std::os::unix::fs::MetadataExt;
+                if group.id == Some(metadata.gid()) {
+                    return true;
+                }
+            }
+
+            Matcher::Image(image) => {
+                if let Some(size) = file.image_size.as_ref().and_then(|j| j.get()) {
+                    let in_range = |value, min: Option<u32>, max: Option<u32>| {
+                        !matches!(min, Some(m) if value < m) && !matches!(max, Some(m) if value > m)
+                    };
+
+                    if in_range(size.width, image.min_width, image.max_width)
+                        && in_range(size.height, image.min_height, image.max_height)
+                    {
+                        return true;
+                    }
+                }
+            }
+
             Matcher::Mime(mime_type) => {
                 let mt = cached_mime_type
                     .get_or_insert_with(|| path.extension().and_then(MimeType::from_extension));
@@ -87,8 +128,24 @@ pub(super) fn is_match<'a>(
                 }
             }
 
+            #[cfg(unix)]
+            Matcher::Mode(mode) => {
+                use std::os::unix::fs::MetadataExt;
+                if mode.is_match(metadata.mode()) {
+                    return true;
+                }
+            }
+
             Matcher::Not(m) => {
-                if !is_match(path, metadata, tree_info, change, include_hidden, [&**m]) {
+                if !is_match(file, include_hidden, [&**m]) {
+                    return true;
+                }
+            }
+
+            #[cfg(unix)]
+            Matcher::Owner(user) => {
+                use std::os::unix::fs::MetadataExt;
+                if user.id == Some(metadata.uid()) {
                     return true;
                 }
             }
@@ -102,6 +159,8 @@ pub(super) fn is_match<'a>(
             }
 
             Matcher::Type(file_type) => {
+                #[cfg(unix)]
+                use crate::config::Mode;
                 #[cfg(unix)]
                 use std::os::unix::fs::{FileTypeExt, MetadataExt};
 
@@ -115,7 +174,9 @@ pub(super) fn is_match<'a>(
                     FileType::Directory => metadata.is_dir(),
 
                     #[cfg(unix)]
-                    FileType::Executable => metadata.is_file() && metadata.mode() & 0o111 != 0,
+                    FileType::Executable => {
+                        metadata.is_file() && Mode::EXECUTABLE.is_match(metadata.mode())
+                    }
 
                     FileType::File => metadata.is_file(),
 
@@ -138,6 +199,49 @@ pub(super) fn is_match<'a>(
     false
 }
 
+/// Returns `true` if the timestamp selected in `range` is within its limits.
+fn is_in_range(file: &File, range: &ChangesRange) -> bool {
+    let timestamp = match timestamp(file, range.timestamp.unwrap_or(Timestamp::Mtime)) {
+        Some(t) => t,
+        None => return false,
+    };
+
+    let now = unix_seconds(SystemTime::now());
+
+    let threshold = |limit: &TimeLimit| match limit {
+        TimeLimit::Age(age) => now - age.as_secs() as i64,
+        TimeLimit::Date(date) => unix_seconds(*date),
+    };
+
+    if matches!(&range.newer_than, Some(l) if timestamp < threshold(l)) {
+        return false;
+    }
+
+    if matches!(&range.older_than, Some(l) if timestamp >= threshold(l)) {
+        return false;
+    }
+
+    if let Some([a, b]) = &range.between {
+        let (a, b) = (threshold(a), threshold(b));
+        if timestamp < a.min(b) || timestamp >= a.max(b) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Read a timestamp of the file, in seconds since the Unix epoch.
+///
+/// For directories, `mtime` is the newest modification time in the tree, if
+/// it is available.
+pub(super) fn timestamp(file: &File, kind: Timestamp) -> Option<i64> {
+    match (kind, file.tree_info.as_ref().and_then(|ti| ti.get())) {
+        (Timestamp::Mtime, Some(ti)) => Some(ti.mtime as i64),
+        _ => exts::timestamp(&file.metadata, kind),
+    }
+}
+
 #[cfg(unix)]
 fn is_hidden_file(name: &OsStr, _: &fs::Metadata) -> bool {
     use std::os::unix::ffi::OsStrExt;
@@ -7,15 +7,27 @@
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::{fs, io};
 
 mod analyzer;
+mod commands;
+mod duplicates;
 mod exts;
+mod fields;
+mod filesystem;
 mod gitdiff;
+mod heatmap;
+mod icons;
+mod images;
 mod info;
+mod jobs;
+mod markers;
 mod matchers;
 mod render;
 mod sorting;
+mod split;
+mod timestamps;
 mod treereader;
 
 #[cfg(test)]
@@ -39,12 +51,14 @@ struct Analysis<'a> {
     variables: HashMap<&'a str, usize>,
     changes: Option<gitdiff::Change>,
     disk_usage_files: u64,
+    markers: Option<u64>,
 }
 
 #[cfg_attr(test, derive(Debug))]
 struct FilesGroup<'a> {
     column: &'a config::Column,
-    files: Vec<File>,
+    label: Option<String>,
+    files: Vec<Rc<File>>,
 }
 
 #[cfg_attr(test, derive(Debug))]
@@ -53,4 +67,8 @@ struct File {
     metadata: fs::Metadata,
     git_changes: Option<gitdiff::Change>,
     tree_info: Option<treereader::TreeInfoJob>,
+    markers: Option<markers::MarkersJob>,
+    duplicate: Option<usize>,
+    image_size: Option<images::ImageSizeJob>,
+    command_values: commands::Values,
 }
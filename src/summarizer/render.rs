@@ -11,33 +11,58 @@
 // [7566]: https://github.com/rust-lang/rust-clippy/pull/7566
 #![allow(clippy::manual_flatten)]
 
+use std::ffi::OsStr;
+use std::num::NonZeroUsize;
 use std::path::Path;
+use std::rc::Rc;
+use std::time::SystemTime;
 use std::{env, mem};
 
-use super::{Analysis, FilesGroup};
-use crate::config;
-use crate::display::{styles, Column, QuotedString, Row, Screen};
+use super::heatmap::Heatmap;
+use super::{fields, icons, Analysis, File, FilesGroup};
+use crate::config::{self, NameWidth};
+use crate::display::{self, styles, Column, QuotedString, Row, Screen, ELLIPSIS};
 
 /// Default padding between columns.
 const DEFAULT_PADDING: usize = 4;
 
+/// Default value for `grid.min_name_width`.
+const DEFAULT_MIN_NAME_WIDTH: usize = 10;
+
 pub(super) fn render_groups<'a>(analysis: &Analysis<'a>, config: &'a config::Root) -> Screen {
     let padding = config.grid.column_padding.unwrap_or(DEFAULT_PADDING);
 
-    let has_labels = analysis.groups.iter().any(|g| g.column.label.is_some());
-
-    let mut columns = Vec::with_capacity(analysis.groups.len() * 2);
-    for group in &analysis.groups {
-        if group.files.is_empty() {
-            continue;
-        }
+    let lscolors = {
+        let var_name = match &config.colors.use_lscolors {
+            config::LsColors::Bool(false) => None,
+            config::LsColors::Bool(true) => Some("LS_COLORS"),
+            config::LsColors::VarName(var) => Some(&var[..]),
+        };
 
-        if !columns.is_empty() {
-            columns.push(Column::padding(padding, 0, None));
-        }
+        var_name
+            .and_then(|name| env::var(name).ok())
+            .map(|l| lscolors::LsColors::from_string(&l))
+    };
 
-        render_group(group, config, has_labels, &mut columns);
-    }
+    let context = Context {
+        config,
+        heatmap: config
+            .colors
+            .heatmap
+            .as_ref()
+            .map(|h| Heatmap::new(analysis, h)),
+        lscolors,
+        ambiguous_width: config
+            .grid
+            .ambiguous_width
+            .unwrap_or(config::AmbiguousWidth::Narrow),
+        has_labels: analysis.groups.iter().any(|g| g.label.is_some()),
+        base_url: if config.colors.hyperlinks {
+            Some(file_url(&analysis.path))
+        } else {
+            None
+        },
+    };
 
     macro_rules! info {
         ($field:ident) => {
@@ -45,33 +70,278 @@ pub(super) fn render_groups<'a>(analysis: &Analysis<'a>, config: &'a config::Roo
                 .info
                 .as_ref()
                 .and_then(|i| i.$field.as_ref())
-                .map(|i| render_info(analysis, i))
+                .map(|i| render_info(analysis, i, &context))
         };
     }
 
+    let info_column = info!(column);
+
+    // Render every group in a single column. If `max_rows` is set, the
+    // content is truncated.
+    let max_rows = config.grid.max_rows.map(|m| m.get());
+    let groups: Vec<_> = analysis
+        .groups
+        .iter()
+        .filter(|g| !g.files.is_empty())
+        .collect();
+
+    let render_block = |group: &FilesGroup, max_name_width| {
+        let (files, more_entries) = match max_rows {
+            Some(max_rows) if max_rows < group.files.len() && group.files.len() > 2 => (
+                &group.files[..max_rows - 1],
+                Some(group.files.len() - max_rows + 1),
+            ),
+
+            _ => (&group.files[..], None),
+        };
+
+        let mut columns = Vec::new();
+        render_group(
+            &context,
+            group,
+            group.label.as_deref(),
+            files,
+            more_entries,
+            max_name_width,
+            &mut columns,
+        );
+        columns
+    };
+
+    let mut name_widths: Vec<_> = groups
+        .iter()
+        .map(|group| match name_width(config, group) {
+            Some(NameWidth::Fixed(width)) => Some(width),
+            _ => None,
+        })
+        .collect();
+
+    let mut blocks: Vec<_> = groups
+        .iter()
+        .zip(&name_widths)
+        .map(|(group, max_name_width)| render_block(group, *max_name_width))
+        .collect();
+
+    let terminal_width = display::terminal_width().map(|w| w.get());
+
+    let used_width = |blocks: &[Vec<Column>]| {
+        blocks.iter().map(|b| block_width(b)).sum::<usize>()
+            + padding * blocks.len().saturating_sub(1)
+            + info_column.as_ref().map(|c| c.width()).unwrap_or(0)
+    };
+
+    // Truncate the names in the columns with an `auto` width, so all groups
+    // fit in the terminal. Without a terminal, names are not truncated.
+    let auto_widths: Vec<_> = groups
+        .iter()
+        .zip(&blocks)
+        .enumerate()
+        .filter(|(_, (group, _))| name_width(config, group) == Some(NameWidth::Auto))
+        .map(|(index, (_, block))| (index, block.last().map(|c| c.width()).unwrap_or(0)))
+        .collect();
+
+    let excess = terminal_width.and_then(|w| used_width(&blocks).checked_sub(w));
+    if let Some(excess) = excess {
+        let min_width = config
+            .grid
+            .min_name_width
+            .map(|w| w.get())
+            .unwrap_or(DEFAULT_MIN_NAME_WIDTH);
+
+        let widths: Vec<_> = auto_widths.iter().map(|(_, w)| *w).collect();
+        if let Some(limit) = shrink_widths(&widths, excess, min_width) {
+            for (index, width) in auto_widths {
+                if width > limit {
+                    name_widths[index] = NonZeroUsize::new(limit);
+                    blocks[index] = render_block(groups[index], name_widths[index]);
+                }
+            }
+        }
+    }
+
+    // Wrap long groups in multiple sub-columns, using the space not used by
+    // the other groups.
+    if let (true, Some(max_rows)) = (config.grid.flow, max_rows) {
+        for (index, group) in groups.iter().enumerate() {
+            if group.files.len() <= max_rows {
+                continue;
+            }
+
+            let available = terminal_width
+                .unwrap_or(display::DEFAULT_WIDTH)
+                .saturating_sub(used_width(&blocks) - block_width(&blocks[index]));
+
+            let max_name_width = name_widths[index];
+            if let Some(flow) = flow_group(
+                &context,
+                group,
+                max_rows,
+                max_name_width,
+                padding,
+                available,
+            ) {
+                blocks[index] = flow;
+            }
+        }
+    }
+
+    let mut columns = Vec::with_capacity(blocks.len() * 2);
+    for block in blocks {
+        if !columns.is_empty() {
+            columns.push(Column::separator(padding));
+        }
+
+        columns.extend(block);
+    }
+
     Screen {
         columns,
         info_left: info!(left),
         info_right: info!(right),
-        info_column: info!(column),
+        info_column,
+    }
+}
+
+/// Data shared by all groups.
+struct Context<'a> {
+    config: &'a config::Root,
+    heatmap: Option<Heatmap<'a>>,
+    lscolors: Option<lscolors::LsColors>,
+    ambiguous_width: config::AmbiguousWidth,
+    has_labels: bool,
+
+    /// URL of the directory, for the hyperlinks.
+    base_url: Option<String>,
+}
+
+/// Setting for the width of the names in a group.
+fn name_width(config: &config::Root, group: &FilesGroup) -> Option<NameWidth> {
+    group.column.max_name_width.or(config.grid.max_name_width)
+}
+
+/// Compute the maximum width for the names columns with an `auto` width.
+///
+/// The widest columns are shrunk first, until the sum of the reductions is at
+/// least `excess`. The limit is never less than `min_width`.
+///
+/// Returns `None` if no column has to be truncated.
+fn shrink_widths(widths: &[usize], excess: usize, min_width: usize) -> Option<usize> {
+    let max = *widths.iter().max()?;
+
+    let mut limit = max;
+    while limit > min_width {
+        let reduction: usize = widths.iter().map(|w| w.saturating_sub(limit)).sum();
+        if reduction >= excess {
+            break;
+        }
+
+        limit -= 1;
     }
+
+    Some(limit).filter(|l| *l < max)
+}
+
+/// Width of the columns generated for a group.
+fn block_width(block: &[Column]) -> usize {
+    block.iter().map(|c| c.width()).sum()
+}
+
+/// Render a group in multiple sub-columns of `max_rows` rows, like `ls -C`.
+///
+/// Sub-columns are added while their width is within `available`. If some
+/// files can't be shown, the last sub-column is truncated.
+///
+/// Returns `None` if only one sub-column fits in the available space.
+fn flow_group(
+    context: &Context,
+    group: &FilesGroup,
+    max_rows: usize,
+    max_name_width: Option<NonZeroUsize>,
+    padding: usize,
+    available: usize,
+) -> Option<Vec<Column>> {
+    let chunks: Vec<_> = group.files.chunks(max_rows).collect();
+
+    let render = |index: usize, files: &[Rc<File>], more_entries| {
+        let label = if index == 0 {
+            group.label.as_deref()
+        } else {
+            None
+        };
+
+        let mut columns = Vec::new();
+        render_group(
+            context,
+            group,
+            label,
+            files,
+            more_entries,
+            max_name_width,
+            &mut columns,
+        );
+        columns
+    };
+
+    let mut full_chunks: Vec<Vec<Column>> = Vec::new();
+    let mut best = None;
+
+    for count in 2..=chunks.len() {
+        // All sub-columns are complete, except the last one if there are more
+        // files than the visible ones.
+        while full_chunks.len() < count - 1 {
+            full_chunks.push(render(full_chunks.len(), chunks[full_chunks.len()], None));
+        }
+
+        let last_index = count - 1;
+        let last = if count == chunks.len() {
+            render(last_index, chunks[last_index], None)
+        } else {
+            let visible = last_index * max_rows + max_rows.saturating_sub(1);
+            render(
+                last_index,
+                &chunks[last_index][..max_rows.saturating_sub(1)],
+                Some(group.files.len() - visible),
+            )
+        };
+
+        let width = full_chunks.iter().map(|c| block_width(c)).sum::<usize>()
+            + block_width(&last)
+            + padding * last_index;
+
+        if width > available {
+            break;
+        }
+
+        best = Some((count, last));
+    }
+
+    let (count, last) = best?;
+    full_chunks.truncate(count - 1);
+    full_chunks.push(last);
+
+    let mut columns = Vec::new();
+    for chunk in full_chunks {
+        if !columns.is_empty() {
+            columns.push(Column::padding(padding, 0, None));
+        }
+
+        columns.extend(chunk);
+    }
+
+    Some(columns)
 }
 
 fn render_group(
+    context: &Context,
     group: &FilesGroup,
-    config: &config::Root,
-    has_labels: bool,
+    label: Option<&str>,
+    files: &[Rc<File>],
+    more_entries: Option<usize>,
+    max_name_width: Option<NonZeroUsize>,
     columns: &mut Vec<Column>,
 ) {
-    // Truncate the column height if `max_rows` is set.
-    let (files, more_entries) = match (config.grid.max_rows, group.files.len()) {
-        (Some(max_rows), len) if max_rows.get() < len && len > 2 => (
-            &group.files[..max_rows.get() - 1],
-            Some(len - max_rows.get() + 1),
-        ),
-
-        _ => (&group.files[..], None),
-    };
+    let config = context.config;
+    let has_labels = context.has_labels;
 
     // For git changes and disk, create columns only if there are
     // data for them.
@@ -82,7 +352,7 @@ fn render_group(
                 let mut column = Column::new(false);
 
                 if has_labels {
-                    column.push(Row::default());
+                    column.push(Row::new(context.ambiguous_width));
                 }
 
                 Some(column)
@@ -92,6 +362,17 @@ fn render_group(
         }
     }
 
+    let mut fields = fields::Fields::new(config);
+
+    let mut field_columns: Vec<_> = group
+        .column
+        .fields
+        .iter()
+        .filter_map(|field| {
+            extra_column!(|file| fields.format(*field, file).is_some()).map(|c| (*field, c))
+        })
+        .collect();
+
     let mut git_added_column = extra_column!(|file| match &file.git_changes {
         Some(gc) => gc.insertions > 0,
         None => false,
@@ -104,42 +385,78 @@ fn render_group(
 
     let mut disk_usage_column = extra_column!(|file| file.tree_info.is_some());
 
-    let lscolors = {
-        let var_name = match &config.colors.use_lscolors {
-            config::LsColors::Bool(false) => None,
-            config::LsColors::Bool(true) => Some("LS_COLORS"),
-            config::LsColors::VarName(var) => Some(&var[..]),
-        };
+    let mut markers_column = extra_column!(|file| match &file.markers {
+        Some(m) => m.get().unwrap_or(0) > 0,
+        None => false,
+    });
 
-        var_name
-            .and_then(|name| env::var(name).ok())
-            .map(|l| lscolors::LsColors::from_string(&l))
+    let mut duplicates_column = extra_column!(|file| file.duplicate.is_some());
+
+    let mut image_size_column = if group.column.image_size {
+        extra_column!(|file| match &file.image_size {
+            Some(j) => j.get().is_some(),
+            None => false,
+        })
+    } else {
+        None
     };
 
+    let mut command_columns: Vec<_> = group
+        .column
+        .commands
+        .iter()
+        .filter_map(|name| {
+            extra_column!(|file| file.command_values.contains_key(name)).map(|c| (name, c))
+        })
+        .collect();
+
     macro_rules! color {
         ($key:ident) => {
             config.colors.$key.as_ref().map(|color| color.style)
         };
     }
 
+    let truncation = group
+        .column
+        .truncation
+        .or(config.grid.truncation)
+        .unwrap_or(config::Truncation::End);
+
+    let quoting_style = config
+        .grid
+        .quoting_style
+        .unwrap_or(config::QuotingStyle::Escape);
+
     let mut names_column = Column::new(true);
     let mut indicators_column = Column::new(false);
     let mut has_indicators = false;
+    let mut icons_column = Column::new(false);
+    let mut has_icons = false;
 
     if has_labels {
-        indicators_column.push(Row::default());
+        indicators_column.push(Row::new(context.ambiguous_width));
+        icons_column.push(Row::new(context.ambiguous_width));
 
-        let mut row = Row::default();
-        if let Some(label) = &group.column.label {
-            row.add_text(label.to_string(), color!(column_label));
+        let mut row = Row::new(context.ambiguous_width);
+        if let Some(label) = label {
+            row.add_text(label, color!(column_label));
         }
 
         names_column.push(row);
     }
 
     for file in files {
+        for (field, column) in &mut field_columns {
+            let mut row = Row::new(context.ambiguous_width);
+            if let Some(value) = fields.format(*field, file) {
+                row.add_text(value, fields.style(*field, file));
+            }
+
+            column.push(row);
+        }
+
         if let Some(column) = git_added_column.as_mut() {
-            let mut row = Row::new();
+            let mut row = Row::new(context.ambiguous_width);
             if let Some(changes) = file.git_changes {
                 if changes.insertions > 0 {
                     let style = color!(diff_added)
@@ -152,7 +469,7 @@ fn render_group(
         }
 
         if let Some(column) = git_deleted_column.as_mut() {
-            let mut row = Row::new();
+            let mut row = Row::new(context.ambiguous_width);
             if let Some(changes) = file.git_changes {
                 if changes.deletions > 0 {
                     let style = color!(diff_deleted)
@@ -166,7 +483,7 @@ fn render_group(
         }
 
         if let Some(column) = disk_usage_column.as_mut() {
-            let mut row = Row::new();
+            let mut row = Row::new(context.ambiguous_width);
             if let Some(ti) = file.tree_info.as_ref().and_then(|ti| ti.get()) {
                 row.add_text(format_size(ti.disk_usage), color!(disk_usage));
             }
@@ -174,32 +491,80 @@ fn render_group(
             column.push(row);
         }
 
+        if let Some(column) = markers_column.as_mut() {
+            let mut row = Row::new(context.ambiguous_width);
+            if let Some(markers) = file.markers.as_ref().and_then(|m| m.get()) {
+                if markers > 0 {
+                    let style = color!(markers)
+                        .or_else(|| Some(styles::Style::new().fg(ansi_term::Color::Yellow)));
+
+                    row.add_text(format!("!{}", markers), style);
+                }
+            }
+
+            column.push(row);
+        }
+
+        if let Some(column) = duplicates_column.as_mut() {
+            let mut row = Row::new(context.ambiguous_width);
+            if let Some(group) = file.duplicate {
+                let style = color!(duplicates)
+                    .or_else(|| Some(styles::Style::new().fg(ansi_term::Color::Cyan)));
+
+                row.add_text(format!("={}", group), style);
+            }
+
+            column.push(row);
+        }
+
+        if let Some(column) = image_size_column.as_mut() {
+            let mut row = Row::new(context.ambiguous_width);
+            if let Some(size) = file.image_size.as_ref().and_then(|j| j.get()) {
+                row.add_text(format!("{}x{}", size.width, size.height), None);
+            }
+
+            column.push(row);
+        }
+
+        for (name, column) in &mut command_columns {
+            let mut row = Row::new(context.ambiguous_width);
+            if let Some(value) = file.command_values.get(*name) {
+                row.add_text(value.as_str(), None);
+            }
+
+            column.push(row);
+        }
+
         let path = Path::new(&file.file_name);
-        let mut indicator = Row::new();
+        let mut indicator = Row::new(context.ambiguous_width);
 
         // Apply styles for this file.
         let mut name_style = styles::Style::new();
 
-        if let Some(lscolors) = lscolors.as_ref() {
+        if let Some(lscolors) = context.lscolors.as_ref() {
             if let Some(style) = lscolors.style_for_path_with_metadata(&path, Some(&file.metadata))
             {
                 name_style = styles::combine(name_style, style.to_ansi_term_style());
             }
         }
 
+        if let Some(style) = context.heatmap.as_ref().and_then(|h| h.style(file)) {
+            name_style = styles::combine(name_style, style);
+        }
+
+        // The icon is taken from the last style with an `icon` field.
+        let mut icon = None;
+
         for style in &config.colors.styles {
-            if super::matchers::is_match(
-                path,
-                &file.metadata,
-                file.tree_info.as_ref(),
-                file.git_changes.as_ref(),
-                true,
-                &style.matchers,
-            ) {
+            if super::matchers::is_match(file, true, &style.matchers) {
                 if let Some(color) = &style.color {
                     name_style = styles::combine(name_style, color.style);
                 }
 
+                if let Some(i) = &style.icon {
+                    icon = Some(i.get());
+                }
+
                 if let Some(i) = &style.indicator {
                     has_indicators = true;
                     let (text, style) = i.get();
@@ -208,23 +573,54 @@ fn render_group(
             }
         }
 
-        let mut row = Row::new();
-        let max_name_width = group.column.max_name_width.or(config.grid.max_name_width);
+        let mut row = Row::new(context.ambiguous_width);
         let name_style = Some(name_style).filter(|s| !s.is_plain());
 
-        let quoted_name = QuotedString::new(path.as_ref(), max_name_width);
-        row.add_text(quoted_name.to_string(), name_style);
+        let link = context.base_url.as_ref().map(|base_url| {
+            let mut url = format!("{}/", base_url.trim_end_matches('/'));
+            percent_encode(&file.file_name, &mut url);
+            url
+        });
+
+        let mut add_name = |text: &str, style| match &link {
+            Some(link) => row.add_link(text, style, link),
+            None => row.add_text(text, style),
+        };
+
+        let quoted_name = QuotedString::new(
+            path.as_ref(),
+            max_name_width,
+            quoting_style,
+            context.ambiguous_width,
+        );
+        let (head, tail) = quoted_name.split(truncation);
+        add_name(&head, name_style);
+
+        if let Some(tail) = tail {
+            add_name(ELLIPSIS, color!(name_ellipsis));
+
+            if !tail.is_empty() {
+                add_name(&tail, name_style);
+            }
+        }
+
+        if icon.is_none() && config.colors.icons {
+            icon = Some((icons::builtin(file), None));
+        }
 
-        if quoted_name.is_truncated() {
-            row.add_text("…", color!(name_ellipsis));
+        let mut icon_row = Row::new(context.ambiguous_width);
+        if let Some((text, style)) = icon {
+            has_icons = true;
+            icon_row.add_text(text, style);
         }
 
         names_column.push(row);
         indicators_column.push(indicator);
+        icons_column.push(icon_row);
     }
 
     if let Some(more_entries) = more_entries {
-        let mut row = Row::new();
+        let mut row = Row::new(context.ambiguous_width);
         row.add_text(format!("+{} entries", more_entries), color!(more_entries));
         names_column.push(row)
     }
@@ -233,7 +629,28 @@ fn render_group(
 
     let column_style = group.column.color.as_ref().map(|c| c.style);
 
-    for column in [git_added_column, git_deleted_column, disk_usage_column] {
+    for (field, mut column) in field_columns {
+        if fields::align_right(field) {
+            column.align_right();
+        }
+
+        column.set_style(column_style);
+        column.set_height(names_column.height());
+        columns.push(column);
+        columns.push(Column::padding(1, names_column.height(), column_style));
+    }
+
+    let mut extra_columns = vec![
+        git_added_column,
+        git_deleted_column,
+        disk_usage_column,
+        markers_column,
+        duplicates_column,
+        image_size_column,
+    ];
+    extra_columns.extend(command_columns.into_iter().map(|(_, c)| Some(c)));
+
+    for column in extra_columns {
         if let Some(mut column) = column {
             column.align_right();
             column.set_style(column_style);
@@ -249,11 +666,18 @@ fn render_group(
         columns.push(indicators_column);
     }
 
+    if has_icons {
+        icons_column.set_style(column_style);
+        icons_column.set_height(names_column.height());
+        columns.push(icons_column);
+        columns.push(Column::padding(1, names_column.height(), column_style));
+    }
+
     names_column.set_style(column_style);
     columns.push(names_column);
 }
 
-fn format_size(mut size: u64) -> String {
+pub(super) fn format_size(mut size: u64) -> String {
     if size < 1024 {
         return size.to_string();
     }
@@ -269,14 +693,23 @@ fn format_size(mut size: u64) -> String {
     format!("{:.0}{}", size, unit)
 }
 
-fn render_info(analysis: &Analysis, info: &config::InfoContent) -> Column {
-    use super::info::{self, Token};
+fn render_info(analysis: &Analysis, info: &config::InfoContent, context: &Context) -> Column {
+    use super::info::{self, FsField, Token};
+    use super::{filesystem, timestamps};
 
     let (text, base_style) = info.get();
     let mut style = base_style;
 
+    // Filesystem data is read only if the format string needs it.
+    let mut fs_space = None;
+    let mut fs_mount = None;
+
     let mut column = Column::new(true);
-    let mut row = Row::default();
+    let time = &context.config.time;
+    let base_url = context.base_url.as_deref();
+
+    let new_row = || Row::new(context.ambiguous_width);
+    let mut row = new_row();
 
     column.set_style(base_style);
 
@@ -286,7 +719,7 @@ fn render_info(analysis: &Analysis, info: &config::InfoContent) -> Column {
                 while let Some(nl) = memchr::memchr(b'\n', text.as_bytes()) {
                     row.add_text(&text[..nl], style);
                     text = &text[nl + 1..];
-                    column.push(mem::take(&mut row));
+                    column.push(mem::replace(&mut row, new_row()));
                 }
 
                 row.add_text(text, style);
@@ -308,7 +741,11 @@ fn render_info(analysis: &Analysis, info: &config::InfoContent) -> Column {
             }
 
             Token::Path => {
-                row.add_text(format!("{}", analysis.path.display()), style);
+                let path = format!("{}", analysis.path.display());
+                match base_url {
+                    Some(url) => row.add_link(path, style, url),
+                    None => row.add_text(path, style),
+                }
             }
 
             Token::PathHome => {
@@ -325,6 +762,22 @@ fn render_info(analysis: &Analysis, info: &config::InfoContent) -> Column {
                 row.add_text(format_size(analysis.disk_usage_files), style);
             }
 
+            Token::Markers => {
+                if let Some(markers) = analysis.markers {
+                    row.add_text(format!("{}", markers), style);
+                }
+            }
+
+            Token::ModificationTime => {
+                if let Ok(mtime) = analysis.path.metadata().and_then(|m| m.modified()) {
+                    let now = SystemTime::now();
+                    row.add_text(
+                        timestamps::format_with(time, mtime, now),
+                        styles::combine_opt(style, timestamps::style(time, mtime, now)),
+                    );
+                }
+            }
+
             Token::AddedLines => {
                 if let Some(changes) = &analysis.changes {
                     row.add_text(format!("{}", changes.insertions), style);
@@ -336,6 +789,39 @@ fn render_info(analysis: &Analysis, info: &config::InfoContent) -> Column {
                     row.add_text(format!("{}", changes.deletions), style);
                 }
             }
+
+            Token::Filesystem(field @ (FsField::Mount | FsField::Type)) => {
+                let mount = fs_mount.get_or_insert_with(|| filesystem::mount(&analysis.path));
+                if let Some(mount) = mount {
+                    let text = match field {
+                        FsField::Mount => format!("{}", mount.point.display()),
+                        _ => mount.fs_type.clone(),
+                    };
+
+                    row.add_text(text, style);
+                }
+            }
+
+            Token::Filesystem(field) => {
+                let space = fs_space.get_or_insert_with(|| filesystem::space(&analysis.path));
+                if let Some(space) = space {
+                    let text = match field {
+                        FsField::Total => format_size(space.total),
+                        FsField::Used => format_size(space.used),
+                        FsField::Free => format_size(space.free),
+                        FsField::Usage => format!("{}%", space.usage()),
+                        FsField::Bar(width) => {
+                            let used = (space.usage() as usize * width + 50) / 100;
+                            let mut bar = "█".repeat(used);
+                            bar.push_str(&"░".repeat(width.saturating_sub(used)));
+                            bar
+                        }
+                        FsField::Mount | FsField::Type => continue,
+                    };
+
+                    row.add_text(text, style);
+                }
+            }
         }
     }
 
@@ -346,6 +832,73 @@ fn render_info(analysis: &Analysis, info: &config::InfoContent) -> Column {
     column
 }
 
+/// Build a `file://` URL for a path.
+fn file_url(path: &Path) -> String {
+    let mut url = format!("file://{}", hostname().unwrap_or_default());
+    percent_encode(path.as_os_str(), &mut url);
+    url
+}
+
+/// Append a string to a URL, encoding any byte not allowed in a path.
+fn percent_encode(s: &OsStr, url: &mut String) {
+    #[cfg(unix)]
+    let bytes = {
+        use std::os::unix::ffi::OsStrExt;
+        s.as_bytes()
+    };
+
+    #[cfg(not(unix))]
+    let bytes = s.to_string_lossy();
+    #[cfg(not(unix))]
+    let bytes = bytes.as_bytes();
+
+    for &byte in bytes {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            url.push(byte as char);
+        } else {
+            url.push_str(&format!("%{:02X}", byte));
+        }
+    }
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<String> {
+    let mut buf: Vec<libc::c_char> = vec![0; 256];
+
+    // SAFETY: `buf.len()` is the size of the buffer. The last byte is
+    //         always a null character.
+    unsafe {
+        if libc::gethostname(buf.as_mut_ptr(), buf.len() - 1) != 0 {
+            return None;
+        }
+
+        let name = std::ffi::CStr::from_ptr(buf.as_ptr());
+        Some(name.to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(not(unix))]
+fn hostname() -> Option<String> {
+    None
+}
+
+#[test]
+fn check_file_urls() {
+    let mut url = String::from("file://host/");
+    percent_encode(OsStr::new("a b/ñ%.txt"), &mut url);
+    assert_eq!(url, "file://host/a%20b/%C3%B1%25.txt");
+}
+
+#[test]
+fn check_shrink_widths() {
+    assert_eq!(shrink_widths(&[], 10, 5), None);
+    assert_eq!(shrink_widths(&[20, 10], 0, 5), None);
+    assert_eq!(shrink_widths(&[20, 10], 5, 5), Some(15));
+    assert_eq!(shrink_widths(&[20, 16], 8, 5), Some(14));
+    assert_eq!(shrink_widths(&[20, 16], 100, 5), Some(5));
+    assert_eq!(shrink_widths(&[4, 3], 100, 5), None);
+}
+
 #[test]
 fn check_size_formats() {
     assert_eq!(format_size(900), "900");
@@ -7,96 +7,242 @@
 //! [`sort`]: self::sort
 //! [`FilesGroup`]: super::FilesGroup
 
-use super::exts::mtime;
-use crate::config::{SortKey, SortOrder, SortSpec};
+use super::exts::{mtime, timestamp};
+use super::{matchers, File};
+use crate::config::{SortKey, SortModifiers, SortOrder, SortSpec, Timestamp};
 
 use std::cmp::Ordering;
 use std::ffi::OsStr;
+use std::fs::Metadata;
 use std::ops::RangeInclusive;
+use std::path::Path;
 
 /// Sort the files in a `FilesGroup`.
+///
+/// Files are compared with every key in the sort specification. If
+/// `git_changes_first` is `true`, files with changes are moved before the
+/// others, like a leading `git_changed` key.
+///
+/// Ties are resolved by the file name, using the order of the last key.
+///
+/// Files matching the `pin` list are moved to the beginning, in the order of
+/// the list.
 pub(super) fn sort(group: &mut super::FilesGroup) {
-    let SortSpec(sort_key, sort_order) = group.column.sort.unwrap_or_default();
-
-    let git_changes_first = group.column.git_changes_first;
-    let sort_desc = sort_order == SortOrder::Desc;
-
-    macro_rules! git_order {
-        ($a:expr, $b:expr) => {
-            if git_changes_first {
-                let a = u8::from($a.git_changes.is_none());
-                let b = u8::from($b.git_changes.is_none());
-                match a.cmp(&b) {
-                    Ordering::Equal => (),
-                    ord if sort_desc => return ord.reverse(),
-                    ord => return ord,
-                }
-            }
-        };
-    }
+    let default_spec = SortSpec::default();
+    let SortSpec(spec) = group.column.sort.as_ref().unwrap_or(&default_spec);
 
-    macro_rules! sort {
-        (|$file:ident| $key:expr) => {
-            group.files.sort_unstable_by(|a, b| {
-                git_order!(a, b);
+    let git_changed = (
+        SortKey::GitChanged,
+        SortOrder::Asc,
+        SortModifiers::default(),
+    );
 
-                let key_a = {
-                    let $file = a;
-                    $key
-                };
+    let mut keys: Vec<_> = spec.iter().collect();
+    if group.column.git_changes_first && !keys.iter().any(|k| k.0 == SortKey::GitChanged) {
+        keys.insert(0, &git_changed);
+    }
 
-                let key_b = {
-                    let $file = b;
-                    $key
-                };
+    let last_order = keys.last().map(|k| k.1).unwrap_or(SortOrder::Asc);
 
-                key_a.cmp(&key_b)
+    group.files.sort_unstable_by(|a, b| {
+        keys.iter()
+            .fold(Ordering::Equal, |ord, (key, order, modifiers)| {
+                ord.then_with(|| match order {
+                    SortOrder::Asc => compare(key, *modifiers, a, b),
+                    SortOrder::Desc => compare(key, *modifiers, b, a),
+                })
             })
-        };
+            .then_with(|| match last_order {
+                SortOrder::Asc => a.file_name.cmp(&b.file_name),
+                SortOrder::Desc => b.file_name.cmp(&a.file_name),
+            })
+    });
+
+    let pin = &group.column.pin;
+    if !pin.is_empty() {
+        group.files.sort_by_cached_key(|file| {
+            pin.iter()
+                .position(|m| matchers::is_match(file, true, Some(m)))
+                .unwrap_or(pin.len())
+        });
+    }
+}
+
+/// Compare two files using a single sort key.
+fn compare(key: &SortKey, modifiers: SortModifiers, a: &File, b: &File) -> Ordering {
+    macro_rules! cmp {
+        (|$file:ident| $key:expr) => {{
+            let key_a = {
+                let $file = a;
+                $key
+            };
+
+            let key_b = {
+                let $file = b;
+                $key
+            };
+
+            key_a.cmp(&key_b)
+        }};
     }
 
-    match sort_key {
+    match key {
+        SortKey::AccessTime => cmp!(|f| timestamp(&f.metadata, Timestamp::Atime)),
+
+        SortKey::BirthTime => cmp!(|f| timestamp(&f.metadata, Timestamp::Btime)),
+
+        SortKey::ChangeTime => cmp!(|f| timestamp(&f.metadata, Timestamp::Ctime)),
+
+        SortKey::Command(name) => {
+            // Values are compared like version strings, so numbers
+            // in them are sorted by their numeric value.
+            match (a.command_values.get(name), b.command_values.get(name)) {
+                (Some(va), Some(vb)) => compare_versions(OsStr::new(va), OsStr::new(vb)),
+                (va, vb) => va.cmp(&vb),
+            }
+        }
+
         SortKey::DeepModificationTime => {
-            sort!(|f| (
-                f.tree_info
-                    .as_ref()
-                    .and_then(|ti| ti.get())
-                    .map(|ti| ti.mtime)
-                    .unwrap_or_else(|| mtime(&f.metadata)),
-                &f.file_name
-            ))
+            cmp!(|f| f
+                .tree_info
+                .as_ref()
+                .and_then(|ti| ti.get())
+                .map(|ti| ti.mtime)
+                .unwrap_or_else(|| mtime(&f.metadata)))
+        }
+
+        SortKey::Extension => {
+            let ext_a = Path::new(&a.file_name).extension();
+            let ext_b = Path::new(&b.file_name).extension();
+            match (ext_a, ext_b) {
+                (Some(ea), Some(eb)) => compare_names(ea, eb, modifiers),
+                (ea, eb) => ea.cmp(&eb),
+            }
         }
 
-        SortKey::Name => sort!(|f| &f.file_name),
+        SortKey::GitChanged => cmp!(|f| f.git_changes.is_none()),
+
+        SortKey::GitChanges => {
+            cmp!(|f| f
+                .git_changes
+                .map(|gc| u64::from(gc.insertions) + u64::from(gc.deletions))
+                .unwrap_or(0))
+        }
+
+        SortKey::Name => compare_names(&a.file_name, &b.file_name, modifiers),
 
         SortKey::Size => {
-            sort!(|f| (
-                f.tree_info
-                    .as_ref()
-                    .and_then(|ti| ti.get())
-                    .map(|ti| ti.disk_usage)
-                    .unwrap_or_else(|| f.metadata.len()),
-                &f.file_name
-            ))
+            cmp!(|f| f
+                .tree_info
+                .as_ref()
+                .and_then(|ti| ti.get())
+                .map(|ti| ti.disk_usage)
+                .unwrap_or_else(|| f.metadata.len()))
         }
 
-        SortKey::ModificationTime => {
-            sort!(|f| (mtime(&f.metadata), &f.file_name))
+        SortKey::ModificationTime => cmp!(|f| mtime(&f.metadata)),
+
+        SortKey::Type => cmp!(|f| type_order(&f.metadata)),
+
+        SortKey::Version => compare_versions(&a.file_name, &b.file_name),
+    }
+}
+
+/// Position of the file type for the `type` sort key: directories, symbolic
+/// links, regular files, and then any other type.
+fn type_order(metadata: &Metadata) -> u8 {
+    let file_type = metadata.file_type();
+    if file_type.is_dir() {
+        0
+    } else if file_type.is_symlink() {
+        1
+    } else if file_type.is_file() {
+        2
+    } else {
+        3
+    }
+}
+
+/// Compare two file names, using the sort modifiers.
+///
+/// Names are compared as `OsStr` values if no modifier is enabled.
+fn compare_names(s1: &OsStr, s2: &OsStr, modifiers: SortModifiers) -> Ordering {
+    let SortModifiers {
+        ignore_case,
+        natural,
+        ignore_punctuation,
+    } = modifiers;
+
+    if !ignore_case && !natural && !ignore_punctuation {
+        return s1.cmp(s2);
+    }
+
+    let (s1, s2) = (s1.to_string_lossy(), s2.to_string_lossy());
+
+    // Skip leading punctuation, unless the name contains nothing else.
+    let skip_punctuation = |s: &str| {
+        if ignore_punctuation {
+            s.find(char::is_alphanumeric).unwrap_or(0)
+        } else {
+            0
         }
+    };
+
+    let mut a = s1[skip_punctuation(&s1)..].chars().peekable();
+    let mut b = s2[skip_punctuation(&s2)..].chars().peekable();
+
+    loop {
+        let (ca, cb) = match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(ca), Some(cb)) => (ca, cb),
+        };
+
+        // Compare numbers by their value. Leading zeros are ignored, so the
+        // longest number (without zeros) is the greatest one.
+        if natural && ca.is_ascii_digit() && cb.is_ascii_digit() {
+            let mut digits_a = String::new();
+            let mut digits_b = String::new();
 
-        SortKey::Version => {
-            group.files.sort_unstable_by(|a, b| {
-                git_order!(a, b);
-                compare_versions(&a.file_name, &b.file_name)
-            });
+            for (iter, digits) in [(&mut a, &mut digits_a), (&mut b, &mut digits_b)] {
+                while let Some(c) = iter.next_if(char::is_ascii_digit) {
+                    digits.push(c);
+                }
+            }
+
+            let digits_a = digits_a.trim_start_matches('0');
+            let digits_b = digits_b.trim_start_matches('0');
+
+            match digits_a
+                .len()
+                .cmp(&digits_b.len())
+                .then_with(|| digits_a.cmp(digits_b))
+            {
+                Ordering::Equal => continue,
+                ord => return ord,
+            }
         }
-    }
 
-    if sort_desc {
-        group.files.reverse();
+        a.next();
+        b.next();
+
+        let (ca, cb) = if ignore_case {
+            (fold_case(ca), fold_case(cb))
+        } else {
+            (ca, cb)
+        };
+
+        if ca != cb {
+            return ca.cmp(&cb);
+        }
     }
 }
 
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
 /// Compare two version strings.
 ///
 /// Implementation is similar to `strverscmp(3)`.
@@ -189,3 +335,44 @@ fn check_compare_versions() {
     check!("aaa10000000000000", "aaa10000000000001", Less);
     check!("aaa90000", "aaa1000000000000000000000", Greater);
 }
+
+#[test]
+fn check_compare_names() {
+    use std::ffi::OsString;
+
+    macro_rules! check {
+        ($a:expr, $b:expr, $ord:ident, $($modifier:ident),*) => {
+            let modifiers = SortModifiers {
+                $($modifier: true,)*
+                ..SortModifiers::default()
+            };
+
+            assert_eq!(
+                compare_names(&OsString::from($a), &OsString::from($b), modifiers),
+                Ordering::$ord
+            )
+        };
+    }
+
+    check!("Zeta", "alpha", Less,);
+    check!("Zeta", "alpha", Greater, ignore_case);
+    check!("ABC", "abc", Equal, ignore_case);
+    check!("Émile", "émile", Equal, ignore_case);
+
+    check!("file10", "file2", Less,);
+    check!("file10", "file2", Greater, natural);
+    check!("file002", "file2", Equal, natural);
+    check!("file2a", "file2b", Less, natural);
+    check!(
+        "v99999999999999999999",
+        "v100000000000000000000",
+        Less,
+        natural
+    );
+    check!("File10", "file9", Greater, natural, ignore_case);
+
+    check!(".profile", "bashrc", Less,);
+    check!(".profile", "bashrc", Greater, ignore_punctuation);
+    check!("_build", "Cargo", Less, ignore_punctuation, ignore_case);
+    check!("...", "..", Greater, ignore_punctuation);
+}
@@ -0,0 +1,238 @@
+//! This module implements the `split` setting of the columns.
+//!
+//! A column with a `split` setting is replaced by one column for every
+//! distinct value of the key. All generated columns share the same
+//! [`config::Column`], so they inherit its sort specification and colors.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use super::exts::mtime;
+use super::fields::Fields;
+use super::timestamps::{self, period_start, unix_seconds, Period};
+use super::{matchers, File, FilesGroup};
+use crate::config::{self, Field, MimeType, SplitKey, Timestamp};
+
+/// Label for the files not included in the generated columns, if the
+/// `other` setting is missing.
+const DEFAULT_OTHER_LABEL: &str = "Other";
+
+/// Like `DEFAULT_OTHER_LABEL`, but when the files are split by modification
+/// time.
+const DEFAULT_OLDER_LABEL: &str = "Older";
+
+/// Buckets used when the files are split by modification time, and the
+/// `buckets` setting is empty. They are computed with the calendar in the
+/// timezone of the `time` section.
+const DEFAULT_BUCKETS: &[(&str, Period)] = &[
+    ("Today", Period::Day),
+    ("This week", Period::Week),
+    ("This month", Period::Month),
+];
+
+/// Replace the groups with a `split` setting with the generated groups.
+pub(super) fn split_groups<'a>(
+    groups: Vec<FilesGroup<'a>>,
+    config: &'a config::Root,
+) -> Vec<FilesGroup<'a>> {
+    let mut keys = Keys {
+        fields: Fields::new(config),
+        now: unix_seconds(SystemTime::now()),
+        buckets: Vec::new(),
+    };
+
+    let mut output = Vec::with_capacity(groups.len());
+
+    for group in groups {
+        let split = match &group.column.split {
+            Some(split) => split,
+            None => {
+                output.push(group);
+                continue;
+            }
+        };
+
+        let time_buckets = is_time_key(split.by);
+        let now = keys.now;
+        keys.buckets = if split.buckets.is_empty() {
+            let timezone = timestamps::timezone(&config.time);
+            DEFAULT_BUCKETS
+                .iter()
+                .map(|&(label, period)| (label.to_string(), period_start(now, period, timezone)))
+                .collect()
+        } else {
+            let mut buckets: Vec<_> = split
+                .buckets
+                .iter()
+                .map(|b| (b.label.clone(), now - b.max_age.0.as_secs() as i64))
+                .collect();
+
+            // Files go to the first bucket that contains them, so the
+            // buckets are sorted by `max_age`.
+            buckets.sort_by_key(|&(_, start)| Reverse(start));
+            buckets
+        };
+
+        let mut buckets: Vec<(String, Vec<Rc<File>>)> = Vec::new();
+        let mut positions = HashMap::new();
+        let mut other = Vec::new();
+
+        for file in group.files {
+            match keys.get(&file, split.by) {
+                Some(value) => {
+                    let index = *positions.entry(value.clone()).or_insert_with(|| {
+                        buckets.push((value, Vec::new()));
+                        buckets.len() - 1
+                    });
+
+                    buckets[index].1.push(file);
+                }
+
+                None => other.push(file),
+            }
+        }
+
+        if time_buckets {
+            // Keep the order of the buckets, from the newest to the oldest.
+            buckets.sort_by_cached_key(|(value, _)| {
+                keys.buckets.iter().position(|(label, _)| label == value)
+            });
+        } else {
+            // Bigger columns first. Ties are sorted by the value.
+            buckets.sort_by(|(va, fa), (vb, fb)| fb.len().cmp(&fa.len()).then_with(|| va.cmp(vb)));
+        }
+
+        let min_files = split.min_files.map(|n| n.get()).unwrap_or(1);
+        let max_columns = split.max_columns.map(|n| n.get()).unwrap_or(usize::MAX);
+
+        // Small time buckets are merged with the next (older) bucket.
+        let mut carried = Vec::new();
+
+        let mut generated = 0;
+        for (value, mut files) in buckets {
+            files.append(&mut carried);
+
+            if generated >= max_columns {
+                other.extend(files);
+                continue;
+            }
+
+            if files.len() < min_files {
+                if time_buckets {
+                    carried = files;
+                } else {
+                    other.extend(files);
+                }
+
+                continue;
+            }
+
+            generated += 1;
+            output.push(FilesGroup {
+                column: group.column,
+                label: Some(label(group.column.label.as_deref(), &value)),
+                files,
+            });
+        }
+
+        other.append(&mut carried);
+
+        if !other.is_empty() {
+            let value = split.other.as_deref().unwrap_or(if time_buckets {
+                DEFAULT_OLDER_LABEL
+            } else {
+                DEFAULT_OTHER_LABEL
+            });
+
+            output.push(FilesGroup {
+                column: group.column,
+                label: Some(label(group.column.label.as_deref(), value)),
+                files: other,
+            });
+        }
+    }
+
+    output
+}
+
+/// Returns `true` if the key puts the files in the time buckets.
+fn is_time_key(key: SplitKey) -> bool {
+    matches!(
+        key,
+        SplitKey::ModificationTime | SplitKey::DeepModificationTime
+    )
+}
+
+/// State to compute the values of the keys.
+struct Keys<'a> {
+    fields: Fields<'a>,
+    now: i64,
+
+    /// Labels of the time buckets, and the oldest timestamp in each one.
+    buckets: Vec<(String, i64)>,
+}
+
+impl Keys<'_> {
+    /// Compute the value of the key for a file.
+    ///
+    /// Files without a value are moved to the `other` column.
+    fn get(&mut self, file: &File, split_key: SplitKey) -> Option<String> {
+        let extension = || Path::new(&file.file_name).extension();
+
+        match split_key {
+            SplitKey::Extension => extension().map(|e| e.to_string_lossy().to_lowercase()),
+
+            SplitKey::Mime => extension()
+                .and_then(MimeType::from_extension)
+                .map(|m| m.name().to_string()),
+
+            SplitKey::GitStatus => Some(
+                if file.git_changes.is_some() {
+                    "modified"
+                } else {
+                    "unmodified"
+                }
+                .to_string(),
+            ),
+
+            SplitKey::Owner => self.fields.format(Field::Owner, file),
+
+            SplitKey::ModificationTime | SplitKey::DeepModificationTime => {
+                let mtime = if matches!(split_key, SplitKey::DeepModificationTime) {
+                    matchers::timestamp(file, Timestamp::Mtime)?
+                } else {
+                    mtime(&file.metadata) as i64
+                };
+
+                self.buckets
+                    .iter()
+                    .find(|(_, start)| mtime >= *start)
+                    .map(|(label, _)| label.clone())
+            }
+        }
+    }
+}
+
+/// Build the label of a generated column.
+///
+/// `%k` in the label of the template column is replaced by the value of the
+/// key. If the label has no `%k`, the value is appended in parentheses, so the
+/// generated columns have different labels. If the template has no label, the
+/// value is used as the label.
+fn label(template: Option<&str>, value: &str) -> String {
+    match template {
+        Some(template) if template.contains("%k") => template.replace("%k", value),
+        Some(template) => format!("{} ({})", template, value),
+        None => value.to_string(),
+    }
+}
+
+#[test]
+fn generated_labels() {
+    assert_eq!(label(None, "rs"), "rs");
+    assert_eq!(label(Some("Files"), "rs"), "Files (rs)");
+    assert_eq!(label(Some("*.%k files"), "rs"), "*.rs files");
+}
@@ -0,0 +1,398 @@
+//! This module formats timestamps, using the settings in the `time` section of
+//! the configuration.
+//!
+//! Dates are computed with the algorithms described in
+//! <http://howardhinnant.github.io/date_algorithms.html>.
+
+use std::fmt::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::{self, TimeFormat, TimeZone};
+use crate::display::styles::Style;
+
+/// Pattern used when `time.format` is not set.
+const DEFAULT_PATTERN: &str = "%Y-%m-%d %H:%M";
+
+const SECS_PER_DAY: i64 = 86400;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Calendar periods, for [`period_start`].
+#[derive(Clone, Copy)]
+pub(super) enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+/// Fields of a timestamp in a specific timezone.
+struct DateTime {
+    timestamp: i64,
+    offset: i64,
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    weekday: usize,
+    yearday: u32,
+}
+
+/// Format `time` according to `format` and `timezone`.
+///
+/// `now` is used to compute relative times.
+pub(super) fn format(
+    time: SystemTime,
+    now: SystemTime,
+    format: Option<&TimeFormat>,
+    timezone: TimeZone,
+) -> String {
+    match format {
+        Some(TimeFormat::Relative) => relative(time, now),
+        Some(TimeFormat::Iso) => pattern(time, timezone, "%Y-%m-%dT%H:%M:%S%:z"),
+        Some(TimeFormat::Pattern(p)) => pattern(time, timezone, p),
+        None => pattern(time, timezone, DEFAULT_PATTERN),
+    }
+}
+
+/// Format `time` with the settings in the `time` section.
+pub(super) fn format_with(config: &config::Time, time: SystemTime, now: SystemTime) -> String {
+    format(time, now, config.format.as_ref(), timezone(config))
+}
+
+/// Timezone in the `time` section. The default is the local timezone.
+pub(super) fn timezone(config: &config::Time) -> TimeZone {
+    config.timezone.unwrap_or(TimeZone::Local)
+}
+
+/// Style for a timestamp, from `time.styles`.
+pub(super) fn style(config: &config::Time, time: SystemTime, now: SystemTime) -> Option<Style> {
+    let age = now.duration_since(time).unwrap_or(Duration::ZERO);
+
+    config
+        .styles
+        .iter()
+        .find(|style| {
+            !matches!(&style.min_age, Some(min) if age < min.0)
+                && !matches!(&style.max_age, Some(max) if age >= max.0)
+        })
+        .map(|style| style.color.style)
+}
+
+/// Returns the timestamp of the beginning of the current day, week (starting
+/// on Monday), or month, in `timezone`.
+pub(super) fn period_start(now: i64, period: Period, timezone: TimeZone) -> i64 {
+    let dt = DateTime::new(now, utc_offset(timezone, now));
+
+    let days = match period {
+        Period::Day => 0,
+        Period::Week => (dt.weekday as i64 + 6) % 7,
+        Period::Month => i64::from(dt.day) - 1,
+    };
+
+    let secs = i64::from(dt.hour * 3600 + dt.minute * 60 + dt.second);
+    let start = now - secs - days * SECS_PER_DAY;
+
+    // The offset can be different at the beginning of the period, if there
+    // is a DST change between both timestamps.
+    start + dt.offset - utc_offset(timezone, start)
+}
+
+/// Seconds since the Unix epoch.
+pub(super) fn unix_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    }
+}
+
+/// Format the difference between `time` and `now`, like `3h ago`.
+fn relative(time: SystemTime, now: SystemTime) -> String {
+    let diff = unix_seconds(now) - unix_seconds(time);
+
+    let secs = diff.abs();
+    let (value, unit) = if secs < 60 {
+        (secs, "s")
+    } else if secs < 3600 {
+        (secs / 60, "m")
+    } else if secs < SECS_PER_DAY {
+        (secs / 3600, "h")
+    } else if secs < SECS_PER_DAY * 7 {
+        (secs / SECS_PER_DAY, "d")
+    } else if secs < SECS_PER_DAY * 30 {
+        (secs / (SECS_PER_DAY * 7), "w")
+    } else if secs < SECS_PER_DAY * 365 {
+        (secs / (SECS_PER_DAY * 30), "mo")
+    } else {
+        (secs / (SECS_PER_DAY * 365), "y")
+    };
+
+    if diff < 0 {
+        format!("in {}{}", value, unit)
+    } else {
+        format!("{}{} ago", value, unit)
+    }
+}
+
+/// Format `time` with `strftime`-like specifiers.
+fn pattern(time: SystemTime, timezone: TimeZone, pattern: &str) -> String {
+    let timestamp = unix_seconds(time);
+    let dt = DateTime::new(timestamp, utc_offset(timezone, timestamp));
+
+    let mut output = String::with_capacity(pattern.len() * 2);
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+
+        let _ = match chars.next() {
+            Some('Y') => write!(output, "{}", dt.year),
+            Some('y') => write!(output, "{:02}", dt.year.rem_euclid(100)),
+            Some('m') => write!(output, "{:02}", dt.month),
+            Some('d') => write!(output, "{:02}", dt.day),
+            Some('e') => write!(output, "{:2}", dt.day),
+            Some('j') => write!(output, "{:03}", dt.yearday),
+            Some('H') => write!(output, "{:02}", dt.hour),
+            Some('I') => write!(output, "{:02}", (dt.hour + 11) % 12 + 1),
+            Some('p') => write!(output, "{}", if dt.hour < 12 { "AM" } else { "PM" }),
+            Some('M') => write!(output, "{:02}", dt.minute),
+            Some('S') => write!(output, "{:02}", dt.second),
+            Some('s') => write!(output, "{}", dt.timestamp),
+            Some('F') => write!(output, "{}-{:02}-{:02}", dt.year, dt.month, dt.day),
+            Some('T') => write!(output, "{:02}:{:02}:{:02}", dt.hour, dt.minute, dt.second),
+            Some('R') => write!(output, "{:02}:{:02}", dt.hour, dt.minute),
+            Some('b') => write!(output, "{}", &MONTHS[dt.month as usize - 1][..3]),
+            Some('B') => write!(output, "{}", MONTHS[dt.month as usize - 1]),
+            Some('a') => write!(output, "{}", &WEEKDAYS[dt.weekday][..3]),
+            Some('A') => write!(output, "{}", WEEKDAYS[dt.weekday]),
+            Some('z') => write_offset(&mut output, dt.offset, ""),
+            Some(':') if chars.as_str().starts_with('z') => {
+                chars.next();
+                write_offset(&mut output, dt.offset, ":")
+            }
+            Some('%') => write!(output, "%"),
+            Some(c) => write!(output, "%{}", c),
+            None => write!(output, "%"),
+        };
+    }
+
+    output
+}
+
+fn write_offset(output: &mut String, offset: i64, separator: &str) -> std::fmt::Result {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.abs() / 60;
+    write!(
+        output,
+        "{}{:02}{}{:02}",
+        sign,
+        minutes / 60,
+        separator,
+        minutes % 60
+    )
+}
+
+/// Offset, in seconds, from UTC for the given timestamp.
+fn utc_offset(timezone: TimeZone, timestamp: i64) -> i64 {
+    match timezone {
+        TimeZone::Utc => 0,
+        TimeZone::Offset(offset) => offset.into(),
+        TimeZone::Local => local_offset(timestamp),
+    }
+}
+
+#[cfg(unix)]
+fn local_offset(timestamp: i64) -> i64 {
+    let timestamp = timestamp as libc::time_t;
+
+    // SAFETY: `localtime_r` only writes to the struct.
+    unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&timestamp, &mut tm).is_null() {
+            0
+        } else {
+            tm.tm_gmtoff as i64
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn local_offset(_: i64) -> i64 {
+    0
+}
+
+impl DateTime {
+    fn new(timestamp: i64, offset: i64) -> DateTime {
+        let local = timestamp + offset;
+        let days = local.div_euclid(SECS_PER_DAY);
+        let secs = local.rem_euclid(SECS_PER_DAY) as u32;
+
+        let (year, month, day) = civil_from_days(days);
+
+        DateTime {
+            timestamp,
+            offset,
+            year,
+            month,
+            day,
+            hour: secs / 3600,
+            minute: secs / 60 % 60,
+            second: secs % 60,
+            weekday: (days + 4).rem_euclid(7) as usize,
+            yearday: (days - days_from_civil(year, 1, 1)) as u32 + 1,
+        }
+    }
+}
+
+/// Convert days since the Unix epoch to a `(year, month, day)` tuple.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Convert a `(year, month, day)` tuple to days since the Unix epoch.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146097 + doe - 719468
+}
+
+#[test]
+fn format_patterns() {
+    use std::time::Duration;
+
+    let time = UNIX_EPOCH + Duration::from_secs(1_632_392_430);
+
+    macro_rules! check {
+        ($tz:expr, $pattern:expr, $expected:expr) => {
+            assert_eq!(pattern(time, $tz, $pattern), $expected);
+        };
+    }
+
+    check!(TimeZone::Utc, DEFAULT_PATTERN, "2021-09-23 10:20");
+    check!(TimeZone::Utc, "%F %T %z", "2021-09-23 10:20:30 +0000");
+    check!(
+        TimeZone::Utc,
+        "%a %e %b %y, %I:%M %p",
+        "Thu 23 Sep 21, 10:20 AM"
+    );
+    check!(
+        TimeZone::Utc,
+        "%A %B %j %s %% %Q",
+        "Thursday September 266 1632392430 % %Q"
+    );
+    check!(
+        TimeZone::Offset(-9 * 3600),
+        "%F %R %:z",
+        "2021-09-23 01:20 -09:00"
+    );
+    check!(TimeZone::Offset(16200), "%d %H:%M %z", "23 14:50 +0430");
+
+    assert_eq!(
+        pattern(UNIX_EPOCH, TimeZone::Utc, "%F %a"),
+        "1970-01-01 Thu"
+    );
+
+    let before_epoch = UNIX_EPOCH - Duration::from_secs(SECS_PER_DAY as u64 + 1);
+    assert_eq!(
+        pattern(before_epoch, TimeZone::Utc, "%F %T %a %j"),
+        "1969-12-30 23:59:59 Tue 364"
+    );
+
+    let leap_day = UNIX_EPOCH + Duration::from_secs(951_782_400);
+    assert_eq!(pattern(leap_day, TimeZone::Utc, "%F %j"), "2000-02-29 060");
+}
+
+#[test]
+fn compute_period_starts() {
+    // Thursday, 2021-09-23 10:20:30 UTC.
+    let now = 1_632_392_430;
+
+    assert_eq!(period_start(now, Period::Day, TimeZone::Utc), 1_632_355_200);
+    assert_eq!(
+        period_start(now, Period::Week, TimeZone::Utc),
+        1_632_096_000
+    );
+    assert_eq!(
+        period_start(now, Period::Month, TimeZone::Utc),
+        1_630_454_400
+    );
+
+    // Midnight in +02:00.
+    assert_eq!(
+        period_start(now, Period::Day, TimeZone::Offset(7200)),
+        1_632_348_000
+    );
+
+    // A period starts at its first second.
+    assert_eq!(
+        period_start(1_632_096_000, Period::Week, TimeZone::Utc),
+        1_632_096_000
+    );
+}
+
+#[test]
+fn format_relative() {
+    use std::time::Duration;
+
+    let now = UNIX_EPOCH + Duration::from_secs(1_632_392_430);
+
+    macro_rules! check {
+        ($secs:expr, $expected:expr) => {
+            let time = UNIX_EPOCH + Duration::from_secs((1_632_392_430 - $secs) as u64);
+            assert_eq!(relative(time, now), $expected);
+        };
+    }
+
+    check!(0, "0s ago");
+    check!(59, "59s ago");
+    check!(300, "5m ago");
+    check!(3 * 3600 + 10, "3h ago");
+    check!(2 * SECS_PER_DAY, "2d ago");
+    check!(15 * SECS_PER_DAY, "2w ago");
+    check!(100 * SECS_PER_DAY, "3mo ago");
+    check!(800 * SECS_PER_DAY, "2y ago");
+    check!(-7200, "in 2h");
+}
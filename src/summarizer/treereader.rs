@@ -12,13 +12,12 @@
 //! In Linux, the collector will not descend directories on other filesystems
 //! (like `du -x`).
 
-use std::cell::RefCell;
 use std::path::Path;
-use std::sync::mpsc;
 use std::time::Instant;
 use std::{cmp, fs};
 
 use super::exts::mtime;
+use super::jobs::Job;
 use crate::config;
 use threadpool::ThreadPool;
 
@@ -41,17 +40,10 @@ pub(super) struct TreeInfo {
 }
 
 /// Results of the disk usage computation.
-#[cfg_attr(test, derive(Debug))]
-pub(super) struct TreeInfoJob(RefCell<TreeInfoInner>);
-
-#[cfg_attr(test, derive(Debug))]
-enum TreeInfoInner {
-    Working(Option<Instant>, mpsc::Receiver<Option<TreeInfo>>),
-    Done(Option<TreeInfo>),
-}
+pub(super) type TreeInfoJob = Job<TreeInfo>;
 
 impl TreeReader {
-    pub fn new(config: &config::Root) -> Option<TreeReader> {
+    pub fn new(config: &config::Root, threadpool: &ThreadPool) -> Option<TreeReader> {
         if !config.collector.disk_usage {
             return None;
         }
@@ -62,63 +54,31 @@ impl TreeReader {
             .as_ref()
             .map(|t| Instant::now() + t.0);
 
-        let threadpool = threadpool::Builder::new().build();
-
         Some(TreeReader {
             deadline,
-            threadpool,
+            threadpool: threadpool.clone(),
         })
     }
 
     /// Read data from the path in a background thread.
     pub fn read_info(&self, path: &Path) -> TreeInfoJob {
-        TreeInfoJob::new(&self.threadpool, path, self.deadline)
-    }
-}
-
-impl TreeInfo {
-    fn new(disk_usage: u64, mtime: u64) -> TreeInfo {
-        TreeInfo { disk_usage, mtime }
-    }
-}
-
-impl TreeInfoJob {
-    fn new(pool: &ThreadPool, path: &Path, deadline: Option<Instant>) -> TreeInfoJob {
-        let (tx, rx) = mpsc::channel();
         let path = path.to_owned();
 
-        pool.execute(move || {
+        Job::spawn(&self.threadpool, self.deadline, move || {
             let metadata = if cfg!(target_os = "linux") {
                 path.parent().and_then(|p| p.metadata().ok())
             } else {
                 None
             };
 
-            let _ = tx.send(read_path(&path, metadata));
-        });
-
-        TreeInfoJob(RefCell::new(TreeInfoInner::Working(deadline, rx)))
+            read_path(&path, metadata)
+        })
     }
+}
 
-    /// Returns the disk usage computed by a background thread.
-    ///
-    /// If the value is still unavailable, it will wait until `deadline`.
-    pub fn get(&self) -> Option<TreeInfo> {
-        let mut inner = self.0.borrow_mut();
-
-        let (deadline, rx) = match &mut *inner {
-            TreeInfoInner::Done(n) => return *n,
-            TreeInfoInner::Working(d, r) => (d, r),
-        };
-
-        let timeout = deadline.map(|dl| dl.saturating_duration_since(Instant::now()));
-        let res = match timeout {
-            Some(t) => rx.recv_timeout(t).ok().flatten(),
-            None => rx.recv().ok().flatten(),
-        };
-
-        *inner = TreeInfoInner::Done(res);
-        res
+impl TreeInfo {
+    fn new(disk_usage: u64, mtime: u64) -> TreeInfo {
+        TreeInfo { disk_usage, mtime }
     }
 }
 
@@ -0,0 +1,42 @@
+#!/bin/bash
+#
+# With `max_name_width: auto`, the widest names are truncated to fit all columns
+# in the terminal. Without a terminal, names are not truncated.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+grid:
+  max_name_width: auto
+  min_name_width: 12
+
+columns:
+  - matchers: [ glob: "a*" ]
+  - matchers: [ glob: "b*" ]
+  - matchers: [ glob: "c*" ]
+    max_name_width: 8
+  - matchers: [ any ]
+EOF
+
+touch a-very-long-file-name-for-the-first-column.txt a-short.txt
+touch b-medium-length-name.txt b.txt
+touch c-fixed-width-column.txt
+
+echo "Wide terminal:"
+COLUMNS=200 $SUMMER -c config.yaml
+
+echo
+echo "Narrow terminal:"
+COLUMNS=80 $SUMMER -c config.yaml
+
+echo
+echo "Minimum width:"
+COLUMNS=40 $SUMMER -c config.yaml
+
+echo
+echo "No terminal:"
+env -u COLUMNS $SUMMER -c config.yaml
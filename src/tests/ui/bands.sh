@@ -0,0 +1,41 @@
+#!/bin/bash
+#
+# Groups that don't fit in the terminal are moved to another band, with their
+# labels. The info column is kept in the first band.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - label: Directories
+    matchers: [ type: directory ]
+
+  - label: Sources
+    matchers: [ glob: "*.rs" ]
+
+  - label: Documents
+    matchers: [ glob: "*.md" ]
+
+  - label: Other
+    matchers: [ any ]
+EOF
+
+mkdir src tests
+touch main.rs lib.rs README.md CHANGELOG.md CONTRIBUTING.md Makefile
+
+COLUMNS=40 $SUMMER -c config.yaml
+
+echo
+echo "With an info column:"
+cat >> config.yaml <<'EOF'
+
+info:
+  column: "%V{files} files"
+  variables:
+    files: [ type: file ]
+EOF
+COLUMNS=60 $SUMMER -c config.yaml
@@ -0,0 +1,51 @@
+#!/bin/bash
+#
+# Match files by ranges in their timestamps.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - label: Old
+    matchers: [ changes: { older_than: "2000-01-01" } ]
+
+  - label: Stale
+    matchers: [ changes: { older_than: 1 year } ]
+
+  - label: Recent
+    matchers: [ changes: { between: [ 1 week, 2 months ] } ]
+
+  - label: Accessed
+    matchers:
+      - changes:
+          timestamp: atime
+          newer_than: "2020-01-01 10:00:00"
+          older_than: "2020-01-02"
+
+  - label: Other
+    matchers: [ any ]
+EOF
+
+touch -d '1999-05-01' a
+touch -d '-3 years' b
+touch -d '-4 weeks' c
+touch -d '-5 months' d
+touch -d '-2 days' e
+
+touch -m -d '-1 day' f
+touch -a -d '2020-01-01 12:00:00' f
+
+# The explicit atime can be ignored in some mounts.
+if [ "$(stat -c %X f)" != "$(date -d '2020-01-01 12:00:00' +%s)" ]
+then
+  echo "atime is not stored in this filesystem" 1>&2
+  exit 77
+fi
+
+touch config.yaml
+
+$SUMMER -c config.yaml
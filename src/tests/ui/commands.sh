@@ -0,0 +1,54 @@
+#!/bin/bash
+#
+# Values from external programs.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+  commands:
+    - name: lines
+      program: bash
+      args: [ -c, 'while IFS= read -r -d "" f; do [ -f "$f" ] && wc -l < "$f" || echo; done' ]
+      timeout: 30s
+
+    - name: first
+      program: bash
+      args: [ -c, 'read -r -d "" f; head -n1 "$f"' ]
+      mode: entry
+      timeout: 30s
+
+    - name: slow
+      program: bash
+      args: [ -c, 'sleep 1 && touch slow-finished' ]
+      timeout: 100ms
+
+columns:
+  - matchers: [ command: { name: first, equals: ok } ]
+    commands: [ lines ]
+    sort: command:lines desc
+
+  - matchers: [ command: { name: lines } ]
+    commands: [ first, slow ]
+
+  - matchers: [ any ]
+EOF
+
+mkdir dir
+seq 10 > a
+seq 2 > b
+seq 5 > c
+printf 'ok\n' > d
+printf 'ok\nok\nok\n' > e
+
+$SUMMER -c config.yaml
+
+# Programs are killed after the timeout, so the file is never created.
+sleep 2
+if [ -e slow-finished ]
+then
+  echo "Program was not killed"
+fi
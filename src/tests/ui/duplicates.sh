@@ -0,0 +1,35 @@
+#!/bin/bash
+#
+# Find files with the same content.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+  duplicates:
+    max_file_size: 1000
+
+colors:
+  when: always
+
+  styles:
+    - matchers: [ duplicate ]
+      color: red
+
+columns:
+  - matchers: [ any ]
+EOF
+
+seq 10 > a
+seq 10 > b
+seq 20 > c
+seq 20 > d
+seq 20 > e
+seq 11 > f
+seq 1000 > g
+seq 1000 > h
+touch i j
+
+$SUMMER -c config.yaml
@@ -0,0 +1,27 @@
+#!/bin/bash
+#
+# Files in non-exclusive columns can appear in the next columns.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - label: Recent
+    matchers: [ changes: 1 day ]
+    exclusive: false
+
+  - label: Sources
+    matchers: [ glob: "*.rs" ]
+
+  - label: Other
+    matchers: [ any ]
+EOF
+
+touch -d -1week old.rs old.txt
+touch new.rs new.txt
+
+$SUMMER -c config.yaml
@@ -0,0 +1,34 @@
+#!/bin/bash
+#
+# Show metadata fields next to the file names.
+
+set -euo pipefail
+
+export TZ=UTC
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - matchers: [ type: directory ]
+    fields: [ permissions ]
+
+  - matchers: [ any ]
+    fields: [ permissions, links, size, mtime ]
+EOF
+
+mkdir -p dir/a dir/b
+seq 1000 > numbers
+printf x > one
+ln one hardlink
+ln -s one link
+
+chmod 755 dir
+chmod 4750 numbers
+chmod 644 one config.yaml
+
+touch -h -d '2021-09-23 10:20:30Z' dir numbers one hardlink link config.yaml
+
+$SUMMER -c config.yaml
@@ -0,0 +1,37 @@
+#!/bin/bash
+#
+# With `grid.flow`, long columns are wrapped in multiple sub-columns.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+grid:
+  max_rows: 4
+  flow: true
+
+columns:
+  - label: Dirs
+    matchers: [ type: directory ]
+
+  - label: Files
+    matchers: [ any ]
+EOF
+
+mkdir dir1 dir2
+touch file{00..20}.txt
+
+echo "All files:"
+COLUMNS=80 $SUMMER -c config.yaml
+
+echo
+echo "Narrow terminal:"
+COLUMNS=50 $SUMMER -c config.yaml
+
+echo
+echo "Without flow:"
+sed -i 's/flow: true/flow: false/' config.yaml
+COLUMNS=80 $SUMMER -c config.yaml
@@ -0,0 +1,32 @@
+#!/bin/bash
+#
+# Widths are computed by grapheme clusters, and truncation does not split them.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - label: Names
+    matchers: [ glob: "*.txt" ]
+    max_name_width: 8
+
+  - label: Other
+    matchers: [ any ]
+EOF
+
+touch \
+  'café-crème.txt' \
+  '👨‍👩‍👧-family.txt' \
+  '🇪🇸🇫🇷🇩🇪.txt' \
+  '±±±'
+
+$SUMMER -c config.yaml
+
+echo
+echo "Ambiguous wide:"
+printf 'grid:\n  ambiguous_width: wide\n' >> config.yaml
+$SUMMER -c config.yaml
@@ -0,0 +1,63 @@
+#!/bin/bash
+#
+# Colors for file names from a heatmap.
+
+set -euo pipefail
+
+cat > size.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+colors:
+  when: always
+  use_lscolors: false
+
+  heatmap:
+    by: size
+    colors: [ "#0000ff", "#ff0000" ]
+
+  styles:
+    - matchers: [ glob: "*.b" ]
+      color: bold
+
+columns:
+  - matchers: [ glob: "*.[ab]" ]
+EOF
+
+cat > age.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+colors:
+  when: always
+  use_lscolors: false
+
+  heatmap:
+    by: age
+    max_age: 100 days
+    colors: [ "#ff0000", "#00ff00", "#0000ff" ]
+
+columns:
+  - matchers: [ glob: "*.c" ]
+EOF
+
+head -c 10 /dev/zero > 1.a
+head -c 20 /dev/zero > 2.a
+head -c 30 /dev/zero > 3.b
+head -c 40 /dev/zero > 4.b
+head -c 50 /dev/zero > 5.a
+
+# Dates are far from the current time, so the colors with `max_age` are
+# saturated, and don't depend on when the test is executed.
+touch -d '2100-01-01 00:00:00' 1.c
+touch -d '2000-01-01 00:00:00' 2.c
+touch -d '1990-01-01 00:00:00' 3.c
+
+$SUMMER -c size.yaml
+$SUMMER -c age.yaml
+
+# Without `max_age`, the position is the percentile of the modification time.
+sed -i '/max_age/d' age.yaml
+$SUMMER -c age.yaml
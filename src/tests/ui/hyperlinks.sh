@@ -0,0 +1,40 @@
+#!/bin/bash
+#
+# File names and the path in the header are hyperlinks, if colors are enabled.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+colors:
+  when: always
+  use_lscolors: false
+  hyperlinks: true
+
+grid:
+  max_name_width: 12
+
+info:
+  left: "%P"
+
+columns:
+  - matchers: [ any ]
+EOF
+
+touch 'a b.txt' very-long-file-name.txt
+
+# Hide the host name (as written by Summer) and the path of the temporary
+# directory.
+mask() {
+  sed -e "s|file://[^/]*$PWD|file://HOST/DIR|g" -e "s|$PWD|DIR|g"
+}
+
+$SUMMER -c config.yaml | mask
+
+echo
+echo "Without colors:"
+sed -i 's/when: always/when: never/' config.yaml
+$SUMMER -c config.yaml | mask
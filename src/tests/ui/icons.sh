@@ -0,0 +1,40 @@
+#!/bin/bash
+#
+# Built-in icons, icons from styles, and alignment of double-width icons.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+colors:
+  icons: true
+
+  styles:
+    - icon: "*"
+      matchers: [ glob: "*.md" ]
+
+    - icon:
+        text: ✓
+      matchers: [ glob: "done*" ]
+
+columns:
+  - label: Files
+    matchers: [ any ]
+    exclude: [ glob: config.yaml ]
+EOF
+
+mkdir dir
+ln -s dir link
+touch a.txt b.png c.mp3 d.zip README.md done.txt unknown.xyz
+touch run.sh
+chmod +x run.sh
+
+$SUMMER -c config.yaml
+
+echo
+echo "Only icons from styles:"
+sed -i 's/icons: true/icons: false/' config.yaml
+$SUMMER -c config.yaml
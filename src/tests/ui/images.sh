@@ -0,0 +1,43 @@
+#!/bin/bash
+#
+# Read dimensions from image headers.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - matchers: [ image: { min_width: 1920 } ]
+    image_size: true
+
+  - matchers: [ mime: image ]
+    image_size: true
+
+  - matchers: [ any ]
+EOF
+
+printf '\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\x07\x80\0\0\x04\x38\x08\x02' > large.png
+printf 'GIF89a\x40\x01\xc8\x00\xf7\x00' > small.gif
+printf '\xff\xd8\xff\xc0\x00\x11\x08\x08\x70\x0f\x00\x03' > photo.jpg
+printf 'not an image' > broken.png
+printf 'GIF89a\x40\x01\xc8\x00\xf7\x00' > image.data
+
+$SUMMER -c config.yaml
+
+echo
+echo "Matcher without the column:"
+cat > config.yaml <<'EOF2'
+collector:
+  disk_usage: false
+  git_diff: false
+  image_size: true
+
+columns:
+  - matchers: [ image: { min_width: 1920 } ]
+  - matchers: [ any ]
+EOF2
+
+$SUMMER -c config.yaml
@@ -0,0 +1,31 @@
+#!/bin/bash
+#
+# Count markers in text files.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+  markers:
+    max_file_size: 100
+
+info:
+  left: "%T markers"
+
+columns:
+  - matchers: [ type: directory ]
+  - matchers: [ any ]
+EOF
+
+mkdir -p src/a .hidden empty
+
+printf 'TODO\nFIXME\n' > src/a/x
+printf 'XXX\n' > src/y
+printf 'TODO\n' > .hidden/z
+printf 'TODO\0' > binary
+printf 'XXX TODO\n' > notes
+seq 1000 | sed 's/$/ TODO/' > large
+
+COLUMNS=40 $SUMMER -c config.yaml
@@ -1,6 +1,7 @@
 #!/bin/bash
 #
-# Dicard columns if they exceeds the maximum width.
+# Move columns to another band if they exceed the maximum width, or discard
+# them if `grid.overflow` is `drop`.
 
 set -euo pipefail
 
@@ -24,3 +25,8 @@ done
 
 export COLUMNS=45
 $SUMMER -c config.yaml
+
+echo
+echo "Drop:"
+printf 'grid:\n  overflow: drop\n' >> config.yaml
+$SUMMER -c config.yaml
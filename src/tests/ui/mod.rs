@@ -3,12 +3,18 @@
 //!
 //! If `$SUMMER_TEST_UI` is `update`, the `.output` files are replaced
 //! with the current output from the scripts.
+//!
+//! Scripts are executed with `TZ=UTC`. A script can exit with status `77` to
+//! be skipped, when the system lacks a feature required by the test.
 
 use std::ffi::OsStr;
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::{env, fs};
 
+/// Exit status for skipped scripts.
+const SKIP_STATUS: i32 = 77;
+
 /// Execute shell scripts in `src/tests/ui`.
 ///
 /// This test is only available on Linux because many scripts relies on
@@ -58,13 +64,17 @@ fn run_scripts() {
             .current_dir(tempdir.path())
             .env("SUMMER", &bin_path)
             .env("LS_COLORS", "")
+            .env("TZ", "UTC")
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .output()
             .unwrap();
 
-        if output.status.success() {
+        if output.status.code() == Some(SKIP_STATUS) {
+            eprintln!("[SKIPPED] {}", source.display());
+            eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+        } else if output.status.success() {
             if update_output {
                 fs::write(expected_output, &output.stdout).unwrap();
             } else {
@@ -0,0 +1,47 @@
+#!/bin/bash
+#
+# Match files by owner and permission bits.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF2'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - label: setuid
+    matchers: [ mode: setuid ]
+
+  - label: setgid
+    matchers: [ mode: setgid ]
+
+  - label: sticky
+    matchers: [ mode: sticky ]
+
+  - label: exec
+    matchers: [ mode: executable ]
+
+  - label: o+w
+    matchers: [ mode: "o+w" ]
+
+  - label: mine
+    matchers: [ all: [ owner: me, mode: "u+w,g-w" ] ]
+
+  - label: other
+    matchers: [ any ]
+EOF2
+
+touch private public shared readonly suid sgid script
+mkdir tmp
+
+chmod 600 private
+chmod 666 public
+chmod 664 shared
+chmod 444 readonly
+chmod 4755 suid
+chmod 2755 sgid
+chmod 744 script
+chmod 1777 tmp
+
+$SUMMER -c config.yaml
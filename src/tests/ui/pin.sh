@@ -0,0 +1,26 @@
+#!/bin/bash
+#
+# Pinned files appear before the others, even if the column is truncated.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+grid:
+  max_rows: 5
+
+columns:
+  - matchers: [ any ]
+    sort: name desc
+    pin:
+      - glob: "README*"
+      - glob: Cargo.toml
+      - glob: Makefile
+EOF
+
+touch {a..z}.rs Makefile README.md README.txt Cargo.toml
+
+$SUMMER -c config.yaml
@@ -0,0 +1,27 @@
+#!/bin/bash
+#
+# Quoting styles for file names with special characters.
+
+set -euo pipefail
+
+touch plain.txt 'two words' "it's" $'new\nline' $'invalid\xFF'
+
+for STYLE in literal escape shell shell-always c
+do
+  cat > config.yaml <<EOF
+collector:
+  disk_usage: false
+  git_diff: false
+
+grid:
+  quoting_style: $STYLE
+
+columns:
+  - matchers: [ glob: "*" ]
+    exclude: [ glob: config.yaml ]
+EOF
+
+  echo "$STYLE:"
+  $SUMMER -c config.yaml
+  echo
+done
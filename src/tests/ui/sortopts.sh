@@ -72,3 +72,82 @@ columns:
 EOF
 
 $SUMMER -c config.yaml
+
+
+#
+# Sort by atime, extension, type, git_changes, and multiple keys.
+
+mkdir ../more_keys
+cd ../more_keys
+
+touch z.rs a.rs b.txt c Makefile
+mkdir src docs
+ln -s a.rs link
+
+seq 10 > Makefile
+seq 10 > z.rs
+
+git init 1>&2
+git add .
+git -c user.email=x -c user.name=x commit -m X 1>&2
+
+seq 5 > Makefile
+seq 20 > z.rs
+
+touch -a -d -3day b.txt
+touch -a -d -2day c
+touch -a -d -1day a.rs
+
+for key in atime extension type "git_changes desc" "type, extension desc, name" "git_changed desc, size"
+do
+  printf "SORT = %s\n" "$key"
+
+  cat > config.yaml <<-EOF
+	collector:
+	  disk_usage: false
+
+	columns:
+	  - matchers: [ any ]
+	    exclude: [ glob: [ .git, config.yaml ] ]
+	    git_changes_first: false
+	    sort: $key
+EOF
+
+  if [ "$key" = atime ]
+  then
+    $SUMMER -c config.yaml | head -3
+  else
+    $SUMMER -c config.yaml
+  fi
+done
+
+
+#
+# Sort modifiers for names.
+
+mkdir ../modifiers
+cd ../modifiers
+
+touch Apple apple .banana _cherry Durian item{1..3}0 item{1..3}
+
+for key in name "name ignore_case natural ignore_punctuation"
+do
+  printf "SORT = %s\n" "$key"
+
+  cat > config.yaml <<-EOF
+	collector:
+	  disk_usage: false
+	  git_diff: false
+
+	grid:
+	  max_rows: 20
+
+	columns:
+	  - matchers: [ any ]
+	    exclude: [ glob: config.yaml ]
+	    include_hidden: true
+	    sort: $key
+EOF
+
+  $SUMMER -c config.yaml
+done
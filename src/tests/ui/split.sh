@@ -0,0 +1,113 @@
+#!/bin/bash
+#
+# Columns with a `split` setting generate one column for every value of the
+# key.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - label: Dirs
+    matchers: [ type: directory ]
+
+  - label: "%k files"
+    matchers: [ any ]
+    sort: name desc
+    split:
+      by: extension
+      max_columns: 2
+      min_files: 2
+      other: Misc
+EOF
+
+mkdir dir1 dir2
+touch a.rs b.rs c.RS d.md e.md f.txt g.txt h.toml Makefile
+
+$SUMMER -c config.yaml
+
+echo
+echo "MIME:"
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - matchers: [ type: file ]
+    split:
+      by: mime
+EOF
+
+touch image.png photo.jpg song.mp3
+
+$SUMMER -c config.yaml
+
+echo
+echo "Time buckets:"
+
+cat > config.yaml <<'EOF2'
+collector:
+  disk_usage: true
+  git_diff: false
+
+columns:
+  - label: "Modified: %k"
+    matchers: [ glob: "*.log" ]
+    split:
+      by: mtime
+
+  - label: "Dirs (%k)"
+    matchers: [ glob: "{logs,downloads}" ]
+    split:
+      by: deep_mtime
+      buckets:
+        - label: Recent
+          max_age: 2 hours
+      other: Old
+EOF2
+
+# Default buckets use the calendar, so the files are far from their limits.
+mkdir logs downloads
+touch a.log
+touch -d -40days c.log
+touch -d -1year d.log e.log
+
+touch logs/x downloads/y
+touch -d -1year logs/x logs downloads
+
+$SUMMER -c config.yaml
+
+echo
+echo "Small and unsorted time buckets:"
+
+cat > config.yaml <<'EOF2'
+collector:
+  disk_usage: false
+  git_diff: false
+
+columns:
+  - label: "%k"
+    matchers: [ glob: "*.tmp" ]
+    split:
+      by: mtime
+      min_files: 2
+      buckets:
+        - label: Week
+          max_age: 7 days
+        - label: Hour
+          max_age: 1 hour
+        - label: Day
+          max_age: 1 day
+EOF2
+
+touch -d -10minutes 1.tmp
+touch -d -5hours 2.tmp 3.tmp
+touch -d -3days 4.tmp
+touch -d -1year 5.tmp
+
+$SUMMER -c config.yaml
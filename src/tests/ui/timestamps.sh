@@ -0,0 +1,58 @@
+#!/bin/bash
+#
+# Format modification times, and apply styles by age.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+colors:
+  when: always
+
+time:
+  format: "%a %d %b %Y, %H:%M:%S %z"
+  timezone: "-03:30"
+  styles:
+    - max_age: 1 day
+      color: bold
+
+    - min_age: 30 days
+      color: dim
+
+columns:
+  - matchers: [ any ]
+    fields: [ mtime ]
+EOF
+
+cat > iso.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+time:
+  format: iso
+  timezone: utc
+
+columns:
+  - matchers: [ glob: "*.txt" ]
+    fields: [ mtime ]
+EOF
+
+touch -d '2021-09-23 10:20:30Z' old.txt
+touch -d '2001-02-03 04:05:06Z' older.txt
+touch -d '@0' epoch.txt
+
+$SUMMER -c config.yaml | grep -a txt
+
+echo
+
+$SUMMER -c iso.yaml
+
+echo
+echo "Header:"
+printf 'info:\n  left: "Modified: %%M"\n' >> iso.yaml
+touch -d '2020-05-06 07:08:09Z' .
+$SUMMER -c iso.yaml
@@ -0,0 +1,33 @@
+#!/bin/bash
+#
+# Truncation strategies for long file names.
+
+set -euo pipefail
+
+cat > config.yaml <<'EOF'
+collector:
+  disk_usage: false
+  git_diff: false
+
+grid:
+  max_name_width: 16
+  truncation: middle
+
+columns:
+  - label: Middle
+    matchers: [ any ]
+    exclusive: false
+
+  - label: Extension
+    matchers: [ any ]
+    exclusive: false
+    truncation: keep-extension
+
+  - label: End
+    matchers: [ any ]
+    truncation: end
+EOF
+
+touch very_long_report_2021_final.pdf 'ｗｉｄｅ_ｃｈａｒａｃｔｅｒｓ.txt'
+
+$SUMMER -c config.yaml